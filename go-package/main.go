@@ -15,7 +15,15 @@ import (
 
 func ParseSDL(sdlString string) (map[string]interface{}, error) {
 	cSDLString := C.CString(sdlString)
-	responsePointer := C.parse_sdl_generate(cSDLString)
+	return handleResponse(C.parse_sdl_generate(cSDLString))
+}
+
+func ParseSDLRedacted(sdlString string) (map[string]interface{}, error) {
+	cSDLString := C.CString(sdlString)
+	return handleResponse(C.parse_sdl_generate_redacted(cSDLString))
+}
+
+func handleResponse(responsePointer *C.char) (map[string]interface{}, error) {
 	defer C.parse_sdl_free(responsePointer)
 
 	if responsePointer != nil {
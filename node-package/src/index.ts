@@ -2,11 +2,14 @@ import { Library } from 'ffi-napi';
 
 const sdlLibrary = Library('libsdl_parser', {
   parse_sdl_generate: ['char *', ['string']],
+  parse_sdl_generate_redacted: ['char *', ['string']],
   parse_sdl_free: ['void', ['char *']],
 });
 
-export default function parseSDL(sdlString: string): Object | null {
-  const responsePointer = sdlLibrary.parse_sdl_generate(sdlString) as any;
+export default function parseSDL(sdlString: string, redact = false): Object | null {
+  const responsePointer = (redact
+    ? sdlLibrary.parse_sdl_generate_redacted(sdlString)
+    : sdlLibrary.parse_sdl_generate(sdlString)) as any;
   let possibleErrorDuringExecution = null;
   try {
     const responseString = responsePointer.readCString();
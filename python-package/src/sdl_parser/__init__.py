@@ -9,6 +9,9 @@ library = ctypes.cdll.LoadLibrary(prefix + "sdl_parser" + extension)
 library.parse_sdl_generate.argtypes = (c_char_p, )
 library.parse_sdl_generate.restype = c_void_p
 
+library.parse_sdl_generate_redacted.argtypes = (c_char_p, )
+library.parse_sdl_generate_redacted.restype = c_void_p
+
 library.parse_sdl_free.argtypes = (c_void_p, )
 
 class SDLLibraryParsingError(Exception):
@@ -23,8 +26,9 @@ def handle_response_json(sdl_result_json):
   else:
     raise SDLLibraryParsingError(result['errorMessage'])
 
-def parse_sdl(string):
-    pointer = library.parse_sdl_generate(string.encode('utf-8'))
+def parse_sdl(string, redact=False):
+    generate = library.parse_sdl_generate_redacted if redact else library.parse_sdl_generate
+    pointer = generate(string.encode('utf-8'))
     try:
         string_result = ctypes.cast(pointer, ctypes.c_char_p).value.decode('utf-8')
         return handle_response_json(string_result)
@@ -26,12 +26,7 @@ unsafe fn pointer_to_string<'a>(raw_pointer: *const c_char) -> Result<&'a str> {
     Ok(raw.to_str()?)
 }
 
-/// # Safety
-///
-/// This function should only be called with a string pointer. In case of input being a null
-/// pointer this function will also return a null pointer
-#[no_mangle]
-pub unsafe extern "C" fn parse_sdl_generate(sdl_string_pointer: *const c_char) -> *mut c_char {
+unsafe fn generate_response(sdl_string_pointer: *const c_char, redact: bool) -> *mut c_char {
     if sdl_string_pointer.is_null() {
         return ptr::null_mut();
     }
@@ -44,7 +39,10 @@ pub unsafe extern "C" fn parse_sdl_generate(sdl_string_pointer: *const c_char) -
         .to_string();
 
         let response = match parse_native_sdl(sdl_string) {
-            Ok(sld_schema) => {
+            Ok(mut sld_schema) => {
+                if redact {
+                    sld_schema.redact_credentials();
+                }
                 let success_response = Response {
                     status: Status::Success,
                     result: Some(sld_schema),
@@ -70,6 +68,27 @@ pub unsafe extern "C" fn parse_sdl_generate(sdl_string_pointer: *const c_char) -
     ptr::null_mut()
 }
 
+/// # Safety
+///
+/// This function should only be called with a string pointer. In case of input being a null
+/// pointer this function will also return a null pointer
+#[no_mangle]
+pub unsafe extern "C" fn parse_sdl_generate(sdl_string_pointer: *const c_char) -> *mut c_char {
+    generate_response(sdl_string_pointer, false)
+}
+
+/// # Safety
+///
+/// This function should only be called with a string pointer. In case of input being a null
+/// pointer this function will also return a null pointer. Role passwords are redacted from the
+/// response
+#[no_mangle]
+pub unsafe extern "C" fn parse_sdl_generate_redacted(
+    sdl_string_pointer: *const c_char,
+) -> *mut c_char {
+    generate_response(sdl_string_pointer, true)
+}
+
 /// # Safety
 ///
 /// This function should only be called with a string pointer. In case of input being a null
@@ -1,9 +1,17 @@
-use anyhow::{Ok, Result};
+use anyhow::{anyhow, Ok, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::{
     common::{HelperSource, Source},
+    constants::{
+        MAXIMUM_CONDITION_GRACE_PERIOD, MAXIMUM_CONDITION_INTERVAL, MAXIMUM_CONDITION_TIMEOUT,
+        MINIMUM_CONDITION_INTERVAL,
+    },
+    environment::{Environment, HelperEnvironment},
+    helpers::Connection,
+    operating_system::OsFamily,
+    script::deserialize_optional_string_to_u32,
     Formalize,
 };
 
@@ -13,7 +21,12 @@ pub struct Condition {
     pub name: Option<String>,
     #[serde(default, alias = "Command", alias = "COMMAND")]
     pub command: Option<String>,
-    #[serde(default, alias = "Interval", alias = "INTERVAL")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_string_to_u32",
+        alias = "Interval",
+        alias = "INTERVAL"
+    )]
     pub interval: Option<u32>,
     #[serde(
         default,
@@ -27,8 +40,139 @@ pub struct Condition {
     pub source: Option<Source>,
     #[serde(alias = "Description", alias = "DESCRIPTION")]
     pub description: Option<String>,
-    #[serde(alias = "Environment", alias = "ENVIRONMENT")]
-    pub environment: Option<Vec<String>>,
+    #[serde(
+        default,
+        rename = "environment",
+        alias = "Environment",
+        alias = "ENVIRONMENT",
+        skip_serializing
+    )]
+    environment_helper: Option<HelperEnvironment>,
+    #[serde(default, skip_deserializing)]
+    pub environment: Option<Environment>,
+    #[serde(
+        default,
+        rename = "supported-os",
+        alias = "Supported-os",
+        alias = "SUPPORTED-OS"
+    )]
+    pub supported_os: Option<Vec<OsFamily>>,
+    #[serde(default, rename = "all-of", alias = "All-of", alias = "ALL-OF")]
+    pub all_of: Option<Vec<String>>,
+    #[serde(default, rename = "any-of", alias = "Any-of", alias = "ANY-OF")]
+    pub any_of: Option<Vec<String>>,
+    #[serde(default, alias = "Not", alias = "NOT")]
+    pub not: Option<String>,
+    #[serde(
+        default,
+        rename = "consecutive-checks",
+        alias = "Consecutive-checks",
+        alias = "CONSECUTIVE-CHECKS"
+    )]
+    pub consecutive_checks: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_string_to_u32",
+        alias = "Timeout",
+        alias = "TIMEOUT"
+    )]
+    pub timeout: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_string_to_u32",
+        rename = "grace-period",
+        alias = "Grace-period",
+        alias = "GRACE-PERIOD"
+    )]
+    pub grace_period: Option<u32>,
+}
+
+impl Condition {
+    pub fn is_composite(&self) -> bool {
+        self.all_of.is_some() || self.any_of.is_some() || self.not.is_some()
+    }
+
+    pub fn referenced_conditions(&self) -> Vec<String> {
+        let mut condition_names = vec![];
+        if let Some(all_of) = &self.all_of {
+            condition_names.extend_from_slice(all_of);
+        }
+        if let Some(any_of) = &self.any_of {
+            condition_names.extend_from_slice(any_of);
+        }
+        if let Some(not) = &self.not {
+            condition_names.push(not.to_owned());
+        }
+        condition_names
+    }
+
+    pub fn transitive_conditions(
+        condition_name: &str,
+        potential_conditions: &Option<Conditions>,
+    ) -> Vec<String> {
+        let mut condition_names = vec![condition_name.to_owned()];
+        let mut index = 0;
+        while index < condition_names.len() {
+            if let Some(condition) = potential_conditions
+                .as_ref()
+                .and_then(|conditions| conditions.get(&condition_names[index]))
+            {
+                for referenced_name in condition.referenced_conditions() {
+                    if !condition_names.contains(&referenced_name) {
+                        condition_names.push(referenced_name);
+                    }
+                }
+            }
+            index += 1;
+        }
+        condition_names
+    }
+
+    pub fn evaluate<F>(
+        condition_name: &str,
+        potential_conditions: &Option<Conditions>,
+        evaluation: ConditionEvaluation,
+        is_leaf_true: &F,
+    ) -> bool
+    where
+        F: Fn(&str) -> bool,
+    {
+        let Some(condition) = potential_conditions
+            .as_ref()
+            .and_then(|conditions| conditions.get(condition_name))
+        else {
+            return false;
+        };
+        let evaluate = |condition_name: &String| {
+            Self::evaluate(
+                condition_name,
+                potential_conditions,
+                evaluation,
+                is_leaf_true,
+            )
+        };
+        if let Some(all_of) = &condition.all_of {
+            return all_of.iter().all(evaluate);
+        }
+        if let Some(any_of) = &condition.any_of {
+            return any_of.iter().any(evaluate);
+        }
+        if let Some(not) = &condition.not {
+            return match evaluation {
+                ConditionEvaluation::Truth => !evaluate(not),
+                ConditionEvaluation::Availability => evaluate(not),
+            };
+        }
+        is_leaf_true(condition_name)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ConditionEvaluation {
+    Truth,
+    /// A `not` Condition is only available when its negated Condition is, since a Condition
+    /// that is never checked can not be observed as false either.
+    Availability,
 }
 
 impl Formalize for Condition {
@@ -36,10 +180,70 @@ impl Formalize for Condition {
         if let Some(source_helper) = &self.source_helper {
             self.source = Some(source_helper.to_owned().into());
         }
+        if let Some(environment_helper) = &self.environment_helper {
+            self.environment = Some(environment_helper.to_owned().try_into()?);
+        }
 
         let has_command = self.command.is_some();
         let has_interval = self.interval.is_some();
         let has_source = self.source.is_some();
+        let composite_count = [
+            self.all_of.is_some(),
+            self.any_of.is_some(),
+            self.not.is_some(),
+        ]
+        .iter()
+        .filter(|is_defined| **is_defined)
+        .count();
+
+        if composite_count > 1 {
+            return Err(anyhow!(
+                "Condition can only have one of All-of, Any-of or Not defined"
+            ));
+        } else if composite_count == 1 && (has_command || has_interval || has_source) {
+            return Err(anyhow!(
+                "Condition must have All-of, Any-of or Not defined without Command, Interval or Source"
+            ));
+        }
+        if self.all_of.as_ref().is_some_and(|all_of| all_of.is_empty())
+            || self.any_of.as_ref().is_some_and(|any_of| any_of.is_empty())
+        {
+            return Err(anyhow!(
+                "Condition with All-of or Any-of must reference at least one Condition"
+            ));
+        }
+        if self.consecutive_checks == Some(0) {
+            return Err(anyhow!("Condition Consecutive-checks must be at least 1"));
+        }
+        if let Some(interval) = self.interval {
+            if !(MINIMUM_CONDITION_INTERVAL..=MAXIMUM_CONDITION_INTERVAL).contains(&interval) {
+                return Err(anyhow!(
+                    "Condition Interval must be between {MINIMUM_CONDITION_INTERVAL} and {MAXIMUM_CONDITION_INTERVAL} seconds"
+                ));
+            }
+        }
+        if let Some(timeout) = self.timeout {
+            if !(1..=MAXIMUM_CONDITION_TIMEOUT).contains(&timeout) {
+                return Err(anyhow!(
+                    "Condition Timeout must be between 1 and {MAXIMUM_CONDITION_TIMEOUT} seconds"
+                ));
+            }
+            if self.interval.is_some_and(|interval| interval > timeout) {
+                return Err(anyhow!(
+                    "Condition Timeout must be greater than or equal to Interval"
+                ));
+            }
+        }
+        if let Some(grace_period) = self.grace_period {
+            if grace_period > MAXIMUM_CONDITION_GRACE_PERIOD {
+                return Err(anyhow!(
+                    "Condition Grace-period must be at most {MAXIMUM_CONDITION_GRACE_PERIOD} seconds"
+                ));
+            }
+            if self.timeout.is_some_and(|timeout| grace_period >= timeout) {
+                return Err(anyhow!("Condition Grace-period must be less than Timeout"));
+            }
+        }
 
         if has_source && (has_command || has_interval) {
             return Err(anyhow::anyhow!(
@@ -60,6 +264,28 @@ impl Formalize for Condition {
 
 pub type Conditions = HashMap<String, Condition>;
 
+impl Connection<Condition> for (&String, &Condition) {
+    fn validate_connections(&self, potential_condition_names: &Option<Vec<String>>) -> Result<()> {
+        let (condition_name, condition) = self;
+        for referenced_name in condition.referenced_conditions() {
+            if referenced_name.eq(*condition_name) {
+                return Err(anyhow!(
+                    "Condition \"{condition_name}\" can not reference itself"
+                ));
+            }
+            let is_known = potential_condition_names
+                .as_ref()
+                .is_some_and(|condition_names| condition_names.contains(&referenced_name));
+            if !is_known {
+                return Err(anyhow!(
+                    "Condition \"{condition_name}\" references Condition \"{referenced_name}\" but it is not found under Scenario Conditions"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -159,4 +385,229 @@ mod tests {
         let condition = serde_yaml::from_str::<Condition>(sdl).unwrap();
         insta::assert_debug_snapshot!(condition);
     }
+
+    #[test]
+    fn composite_conditions_are_parsed() {
+        let sdl = r#"
+            name: test-scenario
+            conditions:
+                port-scanned:
+                    command: /usr/bin/scan-detected.sh
+                    interval: 15
+                    consecutive-checks: 3
+                shell-opened:
+                    source: shell-checker
+                    timeout: 600
+                service-down:
+                    command: /usr/bin/service-down.sh
+                    interval: 30
+                service-up:
+                    not: service-down
+                attacker-progress:
+                    all-of:
+                        - port-scanned
+                        - shell-opened
+                    consecutive-checks: 2
+                any-foothold:
+                    any-of:
+                        - attacker-progress
+                        - service-down
+                    timeout: 3600
+        "#;
+        let conditions = parse_sdl(sdl).unwrap().conditions;
+        insta::with_settings!({sort_maps => true}, {
+                insta::assert_yaml_snapshot!(conditions);
+        });
+    }
+
+    #[test]
+    fn composite_conditions_are_evaluated() {
+        let sdl = r#"
+            name: test-scenario
+            conditions:
+                service-down:
+                    command: /usr/bin/service-down.sh
+                    interval: 30
+                unmonitored:
+                    command: /usr/bin/unmonitored.sh
+                    interval: 30
+                service-up:
+                    not: service-down
+                never-observed:
+                    not: unmonitored
+                any-observed:
+                    any-of:
+                        - service-up
+                        - never-observed
+        "#;
+        let conditions = parse_sdl(sdl).unwrap().conditions;
+        let is_monitored = |condition_name: &str| condition_name == "service-down";
+        let is_available = |condition_name| {
+            Condition::evaluate(
+                condition_name,
+                &conditions,
+                ConditionEvaluation::Availability,
+                &is_monitored,
+            )
+        };
+        let is_true = |condition_name| {
+            Condition::evaluate(
+                condition_name,
+                &conditions,
+                ConditionEvaluation::Truth,
+                &is_monitored,
+            )
+        };
+
+        assert!(is_available("service-up"));
+        assert!(!is_available("never-observed"));
+        assert!(is_available("any-observed"));
+        assert!(!is_true("service-up"));
+        assert!(is_true("never-observed"));
+        assert_eq!(
+            Condition::transitive_conditions("any-observed", &conditions),
+            vec![
+                "any-observed",
+                "service-up",
+                "never-observed",
+                "service-down",
+                "unmonitored"
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Condition can only have one of All-of, Any-of or Not defined")]
+    fn composite_condition_with_multiple_operators_is_rejected() {
+        let sdl = r#"
+            all-of:
+                - condition-1
+            not: condition-2
+        "#;
+        let mut condition = serde_yaml::from_str::<Condition>(sdl).unwrap();
+        condition.formalize().unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Condition must have All-of, Any-of or Not defined without Command, Interval or Source"
+    )]
+    fn composite_condition_with_command_is_rejected() {
+        let sdl = r#"
+            any-of:
+                - condition-1
+            command: executable/path.sh
+            interval: 30
+        "#;
+        let mut condition = serde_yaml::from_str::<Condition>(sdl).unwrap();
+        condition.formalize().unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Condition \"attacker-progress\" references Condition \"shell-opened\" but it is not found under Scenario Conditions"
+    )]
+    fn composite_condition_with_unknown_reference_is_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            conditions:
+                port-scanned:
+                    command: /usr/bin/scan-detected.sh
+                    interval: 15
+                attacker-progress:
+                    all-of:
+                        - port-scanned
+                        - shell-opened
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Cyclic reference detected between composite Conditions")]
+    fn composite_condition_cycle_is_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            conditions:
+                port-scanned:
+                    command: /usr/bin/scan-detected.sh
+                    interval: 15
+                condition-1:
+                    all-of:
+                        - port-scanned
+                        - condition-2
+                condition-2:
+                    any-of:
+                        - condition-3
+                condition-3:
+                    not: condition-1
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Composite Condition \"attacker-progress\" can not be attached to VM \"web\""
+    )]
+    fn composite_condition_on_vm_is_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                web:
+                    type: VM
+                    source: web-server
+                    resources:
+                        cpu: 1
+                        ram: 1 gib
+                    roles:
+                        admin: root
+                    conditions:
+                        attacker-progress: admin
+            conditions:
+                port-scanned:
+                    command: /usr/bin/scan-detected.sh
+                    interval: 15
+                attacker-progress:
+                    all-of:
+                        - port-scanned
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    fn duration_strings_are_parsed() {
+        let sdl = r#"
+            command: executable/path.sh
+            interval: 30s
+            timeout: 1h 30min
+            grace-period: 5min
+        "#;
+        let mut condition = serde_yaml::from_str::<Condition>(sdl).unwrap();
+        condition.formalize().unwrap();
+        assert_eq!(condition.interval, Some(30));
+        assert_eq!(condition.timeout, Some(5400));
+        assert_eq!(condition.grace_period, Some(300));
+    }
+
+    #[test]
+    #[should_panic(expected = "Condition Interval must be between 1 and 86400 seconds")]
+    fn too_long_interval_is_rejected() {
+        let sdl = r#"
+            command: executable/path.sh
+            interval: 2d
+        "#;
+        let mut condition = serde_yaml::from_str::<Condition>(sdl).unwrap();
+        condition.formalize().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Condition Grace-period must be less than Timeout")]
+    fn grace_period_longer_than_timeout_is_rejected() {
+        let sdl = r#"
+            source: digital-library-package
+            timeout: 10min
+            grace-period: 15min
+        "#;
+        let mut condition = serde_yaml::from_str::<Condition>(sdl).unwrap();
+        condition.formalize().unwrap();
+    }
 }
@@ -9,11 +9,35 @@ pub const fn default_node_count() -> i32 {
 
 pub const MAX_LONG_NAME: usize = 35;
 
+pub const MINIMUM_PASSWORD_LENGTH: u32 = 8;
+pub const DEFAULT_PASSWORD_LENGTH: u32 = 16;
+pub const fn default_password_length() -> u32 {
+    DEFAULT_PASSWORD_LENGTH
+}
+
+pub const DEFAULT_METRIC_WEIGHT: u32 = 1;
+
+pub const MINIMUM_CONDITION_INTERVAL: u32 = 1;
+pub const MAXIMUM_CONDITION_INTERVAL: u32 = 24 * 60 * 60;
+pub const MAXIMUM_CONDITION_TIMEOUT: u32 = 7 * 24 * 60 * 60;
+pub const MAXIMUM_CONDITION_GRACE_PERIOD: u32 = 24 * 60 * 60;
+
+pub const MINIMUM_REPORTED_SCRIPT_GAP: f64 = 15.0 * 60.0;
+
+pub const REDACTED_SECRET: &str = "<redacted>";
+
 lazy_static! {
     pub static ref CWE_REGEX: Regex = Regex::new(r"CWE-\d+").unwrap();
+    pub static ref ENVIRONMENT_KEY_REGEX: Regex = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap();
+    pub static ref SSH_PUBLIC_KEY_REGEX: Regex = Regex::new(
+        r"^(ssh-(rsa|dss|ed25519)|ecdsa-sha2-nistp(256|384|521)|sk-(ssh-ed25519|ecdsa-sha2-nistp256)@openssh\.com) [A-Za-z0-9+/]+={0,3}( .*)?$"
+    )
+    .unwrap();
+    pub static ref WINDOWS_PATH_REGEX: Regex = Regex::new(r"^([A-Za-z]:[\\/]|\\\\)").unwrap();
 }
 
 pub const DEFAULT_SPEED_VALUE: f64 = 1.0;
+pub const MINIMUM_STORY_SPEED: f64 = 1.0;
 pub const fn default_speed_value() -> f64 {
     DEFAULT_SPEED_VALUE
 }
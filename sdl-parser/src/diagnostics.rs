@@ -0,0 +1,407 @@
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crate::{constants::MINIMUM_REPORTED_SCRIPT_GAP, timeline::wall_offset, Scenario};
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum ScheduleIssueKind {
+    DuplicateEvent,
+    SimultaneousInjects,
+    ScriptGap,
+    UnscheduledEvent,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleIssue {
+    pub severity: Severity,
+    pub kind: ScheduleIssueKind,
+    pub message: String,
+    pub wall_offsets: Vec<f64>,
+    pub timestamps: Vec<DateTime<FixedOffset>>,
+}
+
+impl ScheduleIssue {
+    fn new(
+        severity: Severity,
+        kind: ScheduleIssueKind,
+        message: String,
+        times: Vec<(f64, Option<DateTime<FixedOffset>>)>,
+    ) -> Self {
+        Self {
+            severity,
+            kind,
+            message,
+            wall_offsets: times.iter().map(|(wall_offset, _)| *wall_offset).collect(),
+            timestamps: times
+                .iter()
+                .filter_map(|(_, timestamp)| *timestamp)
+                .collect(),
+        }
+    }
+}
+
+struct InjectDelivery {
+    wall_offset: f64,
+    timestamp: Option<DateTime<FixedOffset>>,
+    injects: BTreeSet<String>,
+}
+
+fn format_time(wall_offset: f64, timestamp: Option<DateTime<FixedOffset>>) -> String {
+    match timestamp {
+        Some(timestamp) => format!("{wall_offset}s ({})", timestamp.to_rfc3339()),
+        None => format!("{wall_offset}s"),
+    }
+}
+
+impl Scenario {
+    pub fn schedule_diagnostics(&self) -> Result<Vec<ScheduleIssue>> {
+        let timeline = self.timeline()?;
+        let mut issues = vec![];
+
+        let stories = self.stories.to_owned().unwrap_or_default();
+        let mut story_names = stories.keys().collect::<Vec<&String>>();
+        story_names.sort();
+        for story_name in story_names {
+            let story = &stories[story_name];
+            let schedule = story.schedule.as_ref().or(self.schedule.as_ref());
+            let mut scheduled_events = BTreeMap::<&String, Vec<(&String, f64)>>::new();
+            for script_name in story.scripts.iter() {
+                let Some(script) = self
+                    .scripts
+                    .as_ref()
+                    .and_then(|scripts| scripts.get(script_name))
+                else {
+                    continue;
+                };
+                for (event_name, script_offset) in script.events.iter() {
+                    scheduled_events.entry(event_name).or_default().push((
+                        script_name,
+                        wall_offset(story, script, *script_offset as f64),
+                    ));
+                }
+            }
+            for (event_name, mut occurrences) in scheduled_events {
+                if occurrences.len() < 2 {
+                    continue;
+                }
+                occurrences.sort_by(|first, second| {
+                    first.1.total_cmp(&second.1).then(first.0.cmp(second.0))
+                });
+                let times = occurrences
+                    .iter()
+                    .map(|(_, wall_offset)| {
+                        (
+                            *wall_offset,
+                            schedule.map(|schedule| schedule.resolve(*wall_offset)),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let places = occurrences
+                    .iter()
+                    .zip(times.iter())
+                    .map(|((script_name, _), (wall_offset, timestamp))| {
+                        format!(
+                            "Script \"{script_name}\" at {}",
+                            format_time(*wall_offset, *timestamp)
+                        )
+                    })
+                    .collect::<Vec<String>>();
+                issues.push(ScheduleIssue::new(
+                    Severity::Error,
+                    ScheduleIssueKind::DuplicateEvent,
+                    format!(
+                        "Event \"{event_name}\" is scheduled more than once in Story \"{story_name}\": {}",
+                        places.join(", ")
+                    ),
+                    times,
+                ));
+            }
+
+            let mut windows = timeline
+                .scripts
+                .iter()
+                .filter(|window| &window.story == story_name)
+                .collect::<Vec<_>>();
+            windows.sort_by(|first, second| {
+                first
+                    .wall_start
+                    .total_cmp(&second.wall_start)
+                    .then(first.script.cmp(&second.script))
+            });
+            let mut covered_until = None::<(f64, Option<DateTime<FixedOffset>>, &String)>;
+            for window in windows {
+                if let Some((wall_end, end_timestamp, previous_script)) = covered_until {
+                    if window.wall_start - wall_end >= MINIMUM_REPORTED_SCRIPT_GAP {
+                        issues.push(ScheduleIssue::new(
+                            Severity::Warning,
+                            ScheduleIssueKind::ScriptGap,
+                            format!(
+                                "Scripts \"{previous_script}\" and \"{}\" of Story \"{story_name}\" do not overlap and leave a gap of {}s from {} to {}",
+                                window.script,
+                                window.wall_start - wall_end,
+                                format_time(wall_end, end_timestamp),
+                                format_time(window.wall_start, window.start_timestamp)
+                            ),
+                            vec![
+                                (wall_end, end_timestamp),
+                                (window.wall_start, window.start_timestamp),
+                            ],
+                        ));
+                    }
+                }
+                if covered_until.is_none_or(|(wall_end, _, _)| window.wall_end > wall_end) {
+                    covered_until = Some((window.wall_end, window.end_timestamp, &window.script));
+                }
+            }
+        }
+
+        let mut deliveries = BTreeMap::<(String, i64), InjectDelivery>::new();
+        for entry in timeline.entries.iter() {
+            for inject_name in entry.injects.iter() {
+                let to_entities = self
+                    .injects
+                    .as_ref()
+                    .and_then(|injects| injects.get(inject_name))
+                    .and_then(|inject| inject.to_entities.to_owned())
+                    .unwrap_or_default();
+                for entity_name in to_entities {
+                    deliveries
+                        .entry((entity_name, entry.wall_offset.round() as i64))
+                        .or_insert_with(|| InjectDelivery {
+                            wall_offset: entry.wall_offset,
+                            timestamp: entry.timestamp,
+                            injects: BTreeSet::new(),
+                        })
+                        .injects
+                        .insert(format!(
+                            "Inject \"{inject_name}\" of Event \"{}\" in Story \"{}\"",
+                            entry.event, entry.story
+                        ));
+                }
+            }
+        }
+        let mut deliveries = deliveries
+            .into_iter()
+            .map(|((entity_name, _), delivery)| (entity_name, delivery))
+            .collect::<Vec<_>>();
+        deliveries.sort_by(|(first_entity, first), (second_entity, second)| {
+            first
+                .wall_offset
+                .total_cmp(&second.wall_offset)
+                .then(first_entity.cmp(second_entity))
+        });
+        for (
+            entity_name,
+            InjectDelivery {
+                wall_offset,
+                timestamp,
+                injects,
+            },
+        ) in deliveries
+        {
+            if injects.len() < 2 {
+                continue;
+            }
+            issues.push(ScheduleIssue::new(
+                Severity::Warning,
+                ScheduleIssueKind::SimultaneousInjects,
+                format!(
+                    "Entity \"{entity_name}\" receives {} injects at the same time {}: {}",
+                    injects.len(),
+                    format_time(wall_offset, timestamp),
+                    injects.into_iter().collect::<Vec<String>>().join(", ")
+                ),
+                vec![(wall_offset, timestamp)],
+            ));
+        }
+
+        let scheduled_event_names = timeline
+            .entries
+            .iter()
+            .map(|entry| &entry.event)
+            .collect::<HashSet<&String>>();
+        let mut unscheduled_event_names = self
+            .events
+            .iter()
+            .flatten()
+            .map(|(event_name, _)| event_name)
+            .filter(|event_name| !scheduled_event_names.contains(event_name))
+            .collect::<Vec<&String>>();
+        unscheduled_event_names.sort();
+        for event_name in unscheduled_event_names {
+            issues.push(ScheduleIssue::new(
+                Severity::Warning,
+                ScheduleIssueKind::UnscheduledEvent,
+                format!("Event \"{event_name}\" is never scheduled by any Script in a Story"),
+                vec![],
+            ));
+        }
+
+        Ok(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        schedule:
+            start: 2024-05-06T09:00:00Z
+        conditions:
+            condition-1:
+                command: executable/path.sh
+                interval: 30
+        entities:
+            white-team:
+                role: White
+            blue-team:
+                role: Blue
+        injects:
+            phishing-email:
+                source: inject-package
+                from-entity: white-team
+                to-entities:
+                    - blue-team
+            ransom-note:
+                source: inject-package
+                from-entity: white-team
+                to-entities:
+                    - blue-team
+        events:
+            phishing-wave:
+                injects:
+                    - phishing-email
+            ransomware:
+                injects:
+                    - ransom-note
+            forgotten:
+                conditions:
+                    - condition-1
+        scripts:
+            morning:
+                start-time: 0
+                end-time: 2h
+                speed: 1
+                events:
+                    phishing-wave: 1h
+                    ransomware: 1h
+            afternoon:
+                start-time: 4h
+                end-time: 6h
+                speed: 1
+                events:
+                    phishing-wave: 5h
+        stories:
+            day-one:
+                scripts:
+                    - morning
+                    - afternoon
+    "#;
+
+    #[test]
+    fn reports_schedule_issues() {
+        let issues = parse_sdl(SDL).unwrap().schedule_diagnostics().unwrap();
+        insta::assert_yaml_snapshot!(issues);
+    }
+
+    #[test]
+    fn reports_only_script_gaps_over_threshold() {
+        let script_gaps = |afternoon_start: &str| {
+            let sdl = SDL.replace("start-time: 4h", &format!("start-time: {afternoon_start}"));
+            parse_sdl(&sdl)
+                .unwrap()
+                .schedule_diagnostics()
+                .unwrap()
+                .into_iter()
+                .filter(|issue| issue.kind == ScheduleIssueKind::ScriptGap)
+                .map(|issue| issue.wall_offsets)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(script_gaps("2h 14min 59s").is_empty());
+        assert_eq!(script_gaps("2h 15min"), vec![vec![7200.0, 8100.0]]);
+        assert_eq!(script_gaps("2h 15min 1s"), vec![vec![7200.0, 8101.0]]);
+    }
+
+    #[test]
+    fn groups_injects_within_the_same_second() {
+        let sdl = r#"
+            name: test-scenario
+            entities:
+                white-team:
+                    role: White
+                blue-team:
+                    role: Blue
+            injects:
+                phishing-email:
+                    source: inject-package
+                    from-entity: white-team
+                    to-entities:
+                        - blue-team
+                ransom-note:
+                    source: inject-package
+                    from-entity: white-team
+                    to-entities:
+                        - blue-team
+            events:
+                phishing-wave:
+                    injects:
+                        - phishing-email
+                ransomware:
+                    injects:
+                        - ransom-note
+            scripts:
+                real-time:
+                    start-time: 0
+                    end-time: 1h
+                    speed: 1
+                    events:
+                        phishing-wave: 30s
+                fast-forward:
+                    start-time: 0
+                    end-time: 1h
+                    speed: 1.1
+                    events:
+                        ransomware: 33s
+            stories:
+                day-one:
+                    scripts:
+                        - real-time
+                        - fast-forward
+        "#;
+        let simultaneous_injects = parse_sdl(sdl)
+            .unwrap()
+            .schedule_diagnostics()
+            .unwrap()
+            .into_iter()
+            .filter(|issue| issue.kind == ScheduleIssueKind::SimultaneousInjects)
+            .count();
+        assert_eq!(simultaneous_injects, 1);
+    }
+
+    #[test]
+    fn reports_no_issues_for_consistent_schedule() {
+        let sdl = SDL
+            .replace("                    ransomware: 1h\n", "")
+            .replace("start-time: 4h", "start-time: 2h")
+            .replace("                    phishing-wave: 5h\n", "                    ransomware: 5h\n")
+            .replace(
+                "            forgotten:\n                conditions:\n                    - condition-1\n",
+                "",
+            );
+        let issues = parse_sdl(&sdl).unwrap().schedule_diagnostics().unwrap();
+        assert!(issues.iter().all(|issue| issue.severity != Severity::Error));
+        assert!(issues.is_empty());
+    }
+}
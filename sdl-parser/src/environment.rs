@@ -0,0 +1,452 @@
+use anyhow::{anyhow, Result};
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    constants::ENVIRONMENT_KEY_REGEX,
+    helpers::{parse_template, TemplatePart},
+    node::NodeType,
+    Scenario,
+};
+
+/// Entries without a value, such as a bare `FLAG`, are inherited from the runtime environment.
+/// Values may use `${parameters.*}` and `${node.*}`; any other `${...}` is kept as it is written.
+pub type Environment = HashMap<String, Option<String>>;
+
+pub type Parameters = HashMap<String, String>;
+
+#[derive(PartialEq, Eq, Debug, Serialize, Clone)]
+pub enum HelperEnvironment {
+    List(Vec<String>),
+    Map(Vec<(String, Option<String>)>),
+}
+
+struct HelperEnvironmentVisitor;
+
+impl<'de> Visitor<'de> for HelperEnvironmentVisitor {
+    type Value = HelperEnvironment;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of KEY=VALUE strings or a map")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut entries = vec![];
+        while let Some(entry) = seq.next_element::<String>()? {
+            entries.push(entry);
+        }
+        Ok(HelperEnvironment::List(entries))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = vec![];
+        while let Some(entry) = map.next_entry::<String, Option<String>>()? {
+            entries.push(entry);
+        }
+        Ok(HelperEnvironment::Map(entries))
+    }
+}
+
+impl<'de> Deserialize<'de> for HelperEnvironment {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_any(HelperEnvironmentVisitor)
+            .map_err(de::Error::custom)
+    }
+}
+
+impl TryFrom<HelperEnvironment> for Environment {
+    type Error = anyhow::Error;
+
+    fn try_from(helper_environment: HelperEnvironment) -> Result<Self> {
+        let entries = match helper_environment {
+            HelperEnvironment::Map(entries) => entries,
+            HelperEnvironment::List(entries) => entries
+                .into_iter()
+                .map(|entry| match entry.split_once('=') {
+                    Some((key, value)) => (key.to_owned(), Some(value.to_owned())),
+                    None => (entry, None),
+                })
+                .collect(),
+        };
+
+        let mut environment = Environment::new();
+        for (key, value) in entries {
+            if !ENVIRONMENT_KEY_REGEX.is_match(&key) {
+                return Err(anyhow!("Environment key \"{key}\" is not a valid name"));
+            }
+            if environment.insert(key.to_owned(), value).is_some() {
+                return Err(anyhow!(
+                    "Environment key \"{key}\" is defined more than once"
+                ));
+            }
+        }
+        Ok(environment)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct NodeFacts {
+    pub name: String,
+    pub instance: u32,
+    pub ip: Option<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NodeEnvironment {
+    pub features: HashMap<String, Environment>,
+    pub conditions: HashMap<String, Environment>,
+    pub injects: HashMap<String, Environment>,
+}
+
+fn is_environment_reference(reference: &str) -> bool {
+    reference.starts_with("parameters.") || reference.starts_with("node.")
+}
+
+pub fn validate_environment_references(
+    environment: &Environment,
+    potential_parameters: &Option<Parameters>,
+) -> Result<()> {
+    for value in environment.values().flatten() {
+        for part in parse_template(value, Some(is_environment_reference))? {
+            let TemplatePart::Reference(reference) = part else {
+                continue;
+            };
+            match reference.split_once('.') {
+                Some(("parameters", parameter_name)) => {
+                    let has_parameter = potential_parameters
+                        .as_ref()
+                        .is_some_and(|parameters| parameters.contains_key(parameter_name));
+                    if !has_parameter {
+                        return Err(anyhow!(
+                            "Environment references Parameter \"{parameter_name}\" but it is not found under Scenario Parameters"
+                        ));
+                    }
+                }
+                Some(("node", "name" | "instance" | "ip")) => {}
+                _ => {
+                    return Err(anyhow!(
+                        "Environment reference \"{reference}\" is not a known Node fact"
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn resolve_environment(
+    environment: &Environment,
+    potential_parameters: &Option<Parameters>,
+    node_facts: &NodeFacts,
+) -> Result<Environment> {
+    environment
+        .iter()
+        .map(|(key, value)| {
+            let Some(value) = value else {
+                return Ok((key.to_owned(), None));
+            };
+            let resolved_value = parse_template(value, Some(is_environment_reference))?
+                .into_iter()
+                .map(|part| match part {
+                    TemplatePart::Literal(literal) => Ok(literal),
+                    TemplatePart::Reference(reference) => match reference.split_once('.') {
+                        Some(("parameters", parameter_name)) => potential_parameters
+                            .as_ref()
+                            .and_then(|parameters| parameters.get(parameter_name).cloned()),
+                        Some(("node", "name")) => Some(node_facts.name.to_owned()),
+                        Some(("node", "instance")) => Some(node_facts.instance.to_string()),
+                        Some(("node", "ip")) => node_facts.ip.to_owned(),
+                        _ => None,
+                    }
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Environment key \"{key}\": Template value for \"{reference}\" is not known"
+                        )
+                    }),
+                })
+                .collect::<Result<String>>()?;
+            Ok((key.to_owned(), Some(resolved_value)))
+        })
+        .collect()
+}
+
+impl Scenario {
+    pub fn resolve_node_environment(&self, node_facts: &NodeFacts) -> Result<NodeEnvironment> {
+        let node = self
+            .nodes
+            .as_ref()
+            .and_then(|nodes| nodes.get(&node_facts.name))
+            .ok_or_else(|| anyhow!("Node \"{}\" not found under Scenario", node_facts.name))?;
+        let NodeType::VM(vm) = &node.type_field else {
+            return Ok(NodeEnvironment::default());
+        };
+
+        let mut node_environment = NodeEnvironment::default();
+        if let Some(features) = &self.features {
+            for feature_name in vm.features.keys() {
+                if let Some(environment) = features
+                    .get(feature_name)
+                    .and_then(|feature| feature.environment.as_ref())
+                {
+                    node_environment.features.insert(
+                        feature_name.to_owned(),
+                        resolve_environment(environment, &self.parameters, node_facts)?,
+                    );
+                }
+            }
+        }
+        if let Some(conditions) = &self.conditions {
+            for condition_name in vm.conditions.keys() {
+                if let Some(environment) = conditions
+                    .get(condition_name)
+                    .and_then(|condition| condition.environment.as_ref())
+                {
+                    node_environment.conditions.insert(
+                        condition_name.to_owned(),
+                        resolve_environment(environment, &self.parameters, node_facts)?,
+                    );
+                }
+            }
+        }
+        if let Some(injects) = &self.injects {
+            for inject_name in vm.injects.keys() {
+                if let Some(environment) = injects
+                    .get(inject_name)
+                    .and_then(|inject| inject.environment.as_ref())
+                {
+                    node_environment.injects.insert(
+                        inject_name.to_owned(),
+                        resolve_environment(environment, &self.parameters, node_facts)?,
+                    );
+                }
+            }
+        }
+        Ok(node_environment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        parameters:
+            domain: range.local
+            port: 8080
+        nodes:
+            web:
+                type: VM
+                source: web-server
+                resources:
+                    cpu: 2
+                    ram: 2 gib
+                roles:
+                    admin: root
+                features:
+                    web-service: admin
+                conditions:
+                    web-check: admin
+        features:
+            web-service:
+                type: service
+                source: web-service
+                environment:
+                    - SERVER_NAME=${node.name}-${node.instance}.${parameters.domain}
+                    - LISTEN=${node.ip}:${parameters.port}
+                    - PRICE=$$5
+                    - DOMAIN_TEMPLATE=$${parameters.domain}
+        conditions:
+            web-check:
+                command: /usr/bin/check.sh
+                interval: 30
+                environment:
+                    TARGET: http://${node.ip}:${parameters.port}
+                    RETRIES: 3
+    "#;
+
+    #[test]
+    fn parses_list_and_map_environments() {
+        let scenario = parse_sdl(SDL).unwrap();
+        insta::with_settings!({sort_maps => true}, {
+                insta::assert_yaml_snapshot!(scenario);
+        });
+    }
+
+    #[test]
+    fn environment_is_resolved_per_node_instance() {
+        let scenario = parse_sdl(SDL).unwrap();
+        let node_environment = scenario
+            .resolve_node_environment(&NodeFacts {
+                name: "web".to_string(),
+                instance: 1,
+                ip: Some("10.0.0.5".to_string()),
+            })
+            .unwrap();
+
+        let feature_environment = &node_environment.features["web-service"];
+        let value = |key: &str| feature_environment[key].as_deref();
+        assert_eq!(value("SERVER_NAME"), Some("web-1.range.local"));
+        assert_eq!(value("LISTEN"), Some("10.0.0.5:8080"));
+        assert_eq!(value("PRICE"), Some("$5"));
+        assert_eq!(value("DOMAIN_TEMPLATE"), Some("${parameters.domain}"));
+        let condition_environment = &node_environment.conditions["web-check"];
+        assert_eq!(
+            condition_environment["TARGET"].as_deref(),
+            Some("http://10.0.0.5:8080")
+        );
+        assert_eq!(condition_environment["RETRIES"].as_deref(), Some("3"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Environment key \"LISTEN\": Template value for \"node.ip\" is not known"
+    )]
+    fn missing_node_fact_is_reported() {
+        let scenario = parse_sdl(SDL).unwrap();
+        scenario
+            .resolve_node_environment(&NodeFacts {
+                name: "web".to_string(),
+                instance: 0,
+                ip: None,
+            })
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Environment key \"PORT\" is defined more than once")]
+    fn duplicate_list_keys_are_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            features:
+                web-service:
+                    type: service
+                    source: web-service
+                    environment:
+                        - PORT=80
+                        - PORT=8080
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Environment key \"PORT\" is defined more than once")]
+    fn duplicate_map_keys_are_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            injects:
+                inject-1:
+                    source: inject-package
+                    environment:
+                        PORT: 80
+                        PORT: 8080
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    fn pre_structured_environments_are_kept_as_is() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                workstation:
+                    type: VM
+                    source: windows-10
+                    resources:
+                        cpu: 2
+                        ram: 4 gib
+                    features:
+                        shell-profile: user
+                    roles:
+                        user: user
+            features:
+                shell-profile:
+                    type: configuration
+                    source: shell-profile
+                    environment:
+                        - PATH=${HOME}/bin:${PATH}
+                        - DEBUG
+                        - GREETING=$$ hello ${USER
+        "#;
+        let scenario = parse_sdl(sdl).unwrap();
+        let node_environment = scenario
+            .resolve_node_environment(&NodeFacts {
+                name: "workstation".to_string(),
+                instance: 0,
+                ip: None,
+            })
+            .unwrap();
+
+        let feature_environment = &node_environment.features["shell-profile"];
+        assert_eq!(
+            feature_environment["PATH"].as_deref(),
+            Some("${HOME}/bin:${PATH}")
+        );
+        assert_eq!(feature_environment["DEBUG"], None);
+        assert_eq!(
+            feature_environment["GREETING"].as_deref(),
+            Some("$ hello ${USER")
+        );
+    }
+
+    #[test]
+    fn environment_escapes_match_inject_content() {
+        let template = "$$5 for $${parameters.domain} at ${parameters.domain}";
+        let environment = Environment::from([("PRICE".to_string(), Some(template.to_string()))]);
+        let parameters = Some(Parameters::from([(
+            "domain".to_string(),
+            "range.local".to_string(),
+        )]));
+        let resolved_environment = resolve_environment(
+            &environment,
+            &parameters,
+            &NodeFacts {
+                name: "web".to_string(),
+                instance: 0,
+                ip: None,
+            },
+        )
+        .unwrap();
+        let rendered_content = crate::helpers::render_template(template, |reference| {
+            (reference == "parameters.domain").then(|| "range.local".to_string())
+        })
+        .unwrap();
+
+        assert_eq!(
+            resolved_environment["PRICE"].as_deref(),
+            Some("$5 for ${parameters.domain} at range.local")
+        );
+        assert_eq!(resolved_environment["PRICE"], Some(rendered_content));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Environment references Parameter \"domain\" but it is not found under Scenario Parameters"
+    )]
+    fn missing_parameter_is_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            features:
+                web-service:
+                    type: service
+                    source: web-service
+                    environment:
+                        - SERVER_NAME=${parameters.domain}
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+}
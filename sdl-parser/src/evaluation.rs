@@ -3,7 +3,10 @@ use std::collections::HashMap;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::{helpers::Connection, metric::Metric, metric::Metrics, Formalize};
+use crate::{
+    constants::DEFAULT_METRIC_WEIGHT, helpers::Connection, metric::Metric, metric::Metrics,
+    Formalize,
+};
 
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
 pub struct MinScore {
@@ -48,15 +51,69 @@ pub struct Evaluation {
     pub _helper_min_score: Option<HelperScore>,
     #[serde(default, skip_deserializing)]
     pub min_score: Option<MinScore>,
+    #[serde(default, alias = "Weights", alias = "WEIGHTS")]
+    pub weights: Option<HashMap<String, u32>>,
 }
 
 impl Evaluation {
+    pub fn metric_weight(&self, metric_name: &str) -> u32 {
+        self.weights
+            .as_ref()
+            .and_then(|weights| weights.get(metric_name).copied())
+            .unwrap_or(DEFAULT_METRIC_WEIGHT)
+    }
+
+    pub fn weighted_metric_score(&self, metric_name: &str, score: u32) -> Result<u32> {
+        score
+            .checked_mul(self.metric_weight(metric_name))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Evaluation weighted score of Metric \"{metric_name}\" can not exceed {}",
+                    u32::MAX
+                )
+            })
+    }
+
+    pub fn weighted_max_score(&self, metrics: &Metrics) -> Result<u32> {
+        self.metrics
+            .iter()
+            .filter_map(|metric_name| {
+                metrics
+                    .get(metric_name)
+                    .map(|metric| (metric_name, metric.max_score))
+            })
+            .try_fold(0u32, |max_score, (metric_name, metric_max_score)| {
+                max_score
+                    .checked_add(self.weighted_metric_score(metric_name, metric_max_score)?)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Sum of Evaluation weighted metric scores can not exceed {}",
+                            u32::MAX
+                        )
+                    })
+            })
+    }
+
+    pub fn required_score(&self, max_score: u32) -> u32 {
+        match &self.min_score {
+            Some(min_score) => match (min_score.absolute, min_score.percentage) {
+                (Some(absolute), _) => absolute,
+                (None, Some(percentage)) => {
+                    u32::try_from((u64::from(max_score) * u64::from(percentage)).div_ceil(100))
+                        .unwrap_or(u32::MAX)
+                }
+                (None, None) => 0,
+            },
+            None => 0,
+        }
+    }
+
     pub fn validate_evaluation_metric_scores(
         &self,
         potential_metrics: Option<&Metrics>,
     ) -> Result<()> {
         if let Some(metrics) = potential_metrics {
-            let metric_score_sum = metrics.iter().map(|s| s.1.max_score).sum();
+            let metric_score_sum = self.weighted_max_score(metrics)?;
             if let Some(min_score) = &self.min_score {
                 if let Some(absolute_min_score) = min_score.absolute {
                     if absolute_min_score > metric_score_sum {
@@ -115,6 +172,20 @@ impl Formalize for Evaluation {
         if self.metrics.is_empty() {
             return Err(anyhow!("An Evaluation must have at least one Metric"));
         }
+        if let Some(weights) = &self.weights {
+            for (metric_name, weight) in weights.iter() {
+                if !self.metrics.contains(metric_name) {
+                    return Err(anyhow!(
+                        "An Evaluation has a weight for Metric \"{metric_name}\" but it is not under the Evaluation Metrics"
+                    ));
+                }
+                if *weight == 0 {
+                    return Err(anyhow!(
+                        "An Evaluation weight for Metric \"{metric_name}\" can not be 0"
+                    ));
+                }
+            }
+        }
         if let Some(min_score) = &self.min_score {
             if let Some(percentage) = min_score.percentage {
                 if percentage > 100 {
@@ -280,4 +351,155 @@ mod tests {
         let mut evaluation: Evaluation = serde_yaml::from_str(evaluation_string).unwrap();
         assert!(evaluation.formalize().is_err());
     }
+
+    #[test]
+    fn min_score_is_checked_against_own_weighted_metrics() {
+        let sdl = r#"
+            name: test-scenario
+            conditions:
+                condition-1:
+                    command: executable/path.sh
+                    interval: 30
+            metrics:
+                metric-1:
+                    type: MANUAL
+                    artifact: true
+                    max-score: 10
+                metric-2:
+                    type: CONDITIONAL
+                    max-score: 10
+                    condition: condition-1
+                metric-3:
+                    type: MANUAL
+                    max-score: 100
+            evaluations:
+                evaluation-1:
+                    metrics:
+                        - metric-1
+                        - metric-2
+                    weights:
+                        metric-1: 3
+                    min-score:
+                        absolute: 40
+        "#;
+        let scenario = parse_sdl(sdl).unwrap();
+        let evaluation = &scenario.evaluations.as_ref().unwrap()["evaluation-1"];
+        assert_eq!(
+            evaluation
+                .weighted_max_score(scenario.metrics.as_ref().unwrap())
+                .unwrap(),
+            40
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Sum of metric scores has to be smaller than the evaluation min-score"
+    )]
+    fn min_score_ignores_metrics_outside_evaluation() {
+        let sdl = r#"
+            name: test-scenario
+            metrics:
+                metric-1:
+                    type: MANUAL
+                    max-score: 10
+                metric-2:
+                    type: MANUAL
+                    max-score: 100
+            evaluations:
+                evaluation-1:
+                    metrics:
+                        - metric-1
+                    min-score:
+                        absolute: 50
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    fn fails_with_weight_for_foreign_metric() {
+        let evaluation_string = r#"
+            metrics:
+                - metric-1
+            weights:
+                metric-2: 2
+            min-score: 50
+        "#;
+        let mut evaluation: Evaluation = serde_yaml::from_str(evaluation_string).unwrap();
+        assert!(evaluation.formalize().is_err());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Evaluation weighted score of Metric \"metric-1\" can not exceed 4294967295"
+    )]
+    fn fails_with_overflowing_metric_weight() {
+        let sdl = r#"
+            name: test-scenario
+            metrics:
+                metric-1:
+                    type: MANUAL
+                    max-score: 10
+            evaluations:
+                evaluation-1:
+                    metrics:
+                        - metric-1
+                    weights:
+                        metric-1: 4294967295
+                    min-score:
+                        absolute: 10
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Sum of Evaluation weighted metric scores can not exceed 4294967295")]
+    fn fails_with_overflowing_weighted_metric_sum() {
+        let sdl = r#"
+            name: test-scenario
+            metrics:
+                metric-1:
+                    type: MANUAL
+                    max-score: 10
+                metric-2:
+                    type: MANUAL
+                    max-score: 10
+            evaluations:
+                evaluation-1:
+                    metrics:
+                        - metric-1
+                        - metric-2
+                    weights:
+                        metric-1: 429496729
+                        metric-2: 429496729
+                    min-score:
+                        absolute: 10
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    fn required_percentage_score_does_not_overflow() {
+        let sdl = r#"
+            name: test-scenario
+            metrics:
+                metric-1:
+                    type: MANUAL
+                    max-score: 10
+            evaluations:
+                evaluation-1:
+                    metrics:
+                        - metric-1
+                    weights:
+                        metric-1: 429496729
+                    min-score: 50
+        "#;
+        let scenario = parse_sdl(sdl).unwrap();
+        let evaluation = &scenario.evaluations.as_ref().unwrap()["evaluation-1"];
+        let max_score = evaluation
+            .weighted_max_score(scenario.metrics.as_ref().unwrap())
+            .unwrap();
+        assert_eq!(max_score, 4294967290);
+        assert_eq!(evaluation.required_score(max_score), 2147483645);
+    }
 }
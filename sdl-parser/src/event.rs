@@ -7,9 +7,54 @@ use crate::{
     condition::Condition,
     helpers::Connection,
     inject::Inject,
+    script::deserialize_optional_string_to_u32,
     Formalize,
 };
 
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub enum TriggerType {
+    #[default]
+    #[serde(alias = "schedule", alias = "SCHEDULE")]
+    Schedule,
+    #[serde(alias = "conditions", alias = "CONDITIONS")]
+    Conditions,
+    #[serde(alias = "after", alias = "AFTER")]
+    After,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub enum TriggerMode {
+    #[default]
+    #[serde(alias = "all", alias = "ALL")]
+    All,
+    #[serde(alias = "any", alias = "ANY")]
+    Any,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Trigger {
+    #[serde(default, rename = "type", alias = "Type", alias = "TYPE")]
+    pub trigger_type: TriggerType,
+    #[serde(default, alias = "Mode", alias = "MODE")]
+    pub mode: Option<TriggerMode>,
+    #[serde(default, alias = "Event", alias = "EVENT")]
+    pub event: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_string_to_u32",
+        alias = "Delay",
+        alias = "DELAY"
+    )]
+    pub delay: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_string_to_u32",
+        alias = "Repeat",
+        alias = "REPEAT"
+    )]
+    pub repeat: Option<u32>,
+}
+
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct Event {
     #[serde(default, alias = "Name", alias = "NAME")]
@@ -30,6 +75,8 @@ pub struct Event {
     pub injects: Option<Vec<String>>,
     #[serde(alias = "Description", alias = "DESCRIPTION")]
     pub description: Option<String>,
+    #[serde(default, alias = "Trigger", alias = "TRIGGER")]
+    pub trigger: Option<Trigger>,
 }
 
 pub type Events = HashMap<String, Event>;
@@ -44,6 +91,115 @@ impl Formalize for Event {
             }
         }
 
+        if let Some(trigger) = &self.trigger {
+            let has_conditions = self
+                .conditions
+                .as_ref()
+                .is_some_and(|conditions| !conditions.is_empty());
+            match trigger.trigger_type {
+                TriggerType::After if trigger.event.is_none() => {
+                    return Err(anyhow!(
+                        "Event Trigger of type After must have Event defined"
+                    ));
+                }
+                TriggerType::Schedule | TriggerType::Conditions if trigger.event.is_some() => {
+                    return Err(anyhow!(
+                        "Event Trigger Event can only be defined for After triggers"
+                    ));
+                }
+                TriggerType::Schedule if trigger.delay.is_some() => {
+                    return Err(anyhow!(
+                        "Event Trigger Delay can not be defined for Schedule triggers"
+                    ));
+                }
+                TriggerType::Conditions if !has_conditions => {
+                    return Err(anyhow!(
+                        "Event Trigger of type Conditions requires Event Conditions"
+                    ));
+                }
+                _ => {}
+            }
+            if trigger.mode.is_some() && !has_conditions {
+                return Err(anyhow!("Event Trigger Mode requires Event Conditions"));
+            }
+            if trigger.repeat == Some(0) {
+                return Err(anyhow!("Event Trigger Repeat must be greater than 0"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Event {
+    pub fn trigger_type(&self) -> TriggerType {
+        self.trigger
+            .as_ref()
+            .map(|trigger| trigger.trigger_type)
+            .unwrap_or_default()
+    }
+
+    pub fn triggered_after(&self) -> Option<&String> {
+        self.trigger
+            .as_ref()
+            .filter(|trigger| trigger.trigger_type == TriggerType::After)
+            .and_then(|trigger| trigger.event.as_ref())
+    }
+
+    pub fn trigger_delay(&self) -> u32 {
+        self.trigger
+            .as_ref()
+            .and_then(|trigger| trigger.delay)
+            .unwrap_or_default()
+    }
+
+    pub fn trigger_repeat(&self) -> Option<u32> {
+        self.trigger.as_ref().and_then(|trigger| trigger.repeat)
+    }
+
+    pub fn are_conditions_met<F>(&self, is_condition_met: F) -> bool
+    where
+        F: Fn(&str) -> bool,
+    {
+        let Some(conditions) = &self.conditions else {
+            return true;
+        };
+        match self
+            .trigger
+            .as_ref()
+            .and_then(|trigger| trigger.mode)
+            .unwrap_or_default()
+        {
+            TriggerMode::All => conditions
+                .iter()
+                .all(|condition_name| is_condition_met(condition_name)),
+            TriggerMode::Any => conditions
+                .iter()
+                .any(|condition_name| is_condition_met(condition_name)),
+        }
+    }
+}
+
+impl Connection<Event> for (&String, &Event) {
+    fn validate_connections(&self, potential_event_names: &Option<Vec<String>>) -> Result<()> {
+        if let Some(trigger_event_name) = self.1.triggered_after() {
+            if trigger_event_name == self.0 {
+                return Err(anyhow!(
+                    "Event \"{event_name}\" can not be triggered after itself",
+                    event_name = self.0
+                ));
+            }
+            if !potential_event_names
+                .iter()
+                .flatten()
+                .any(|event_name| event_name == trigger_event_name)
+            {
+                return Err(anyhow!(
+                    "Event \"{trigger_event_name}\" not found under Scenario"
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -221,4 +377,113 @@ mod tests {
             "#;
         parse_sdl(sdl).unwrap();
     }
+
+    const TRIGGER_SDL: &str = r#"
+        name: test-scenario
+        conditions:
+            condition-1:
+                command: executable/path.sh
+                interval: 30
+            condition-2:
+                command: executable/path.sh
+                interval: 30
+        injects:
+            my-cool-inject:
+                source: inject-package
+        events:
+            breach:
+                conditions:
+                    - condition-1
+                    - condition-2
+                trigger:
+                    type: conditions
+                    mode: any
+                    delay: 5min
+            ransom-note:
+                injects:
+                    - my-cool-inject
+                trigger:
+                    type: after
+                    event: breach
+                    delay: 10min
+                    repeat: 30min
+        scripts:
+            main-script:
+                start-time: 0
+                end-time: 2h
+                speed: 1
+                events:
+                    breach: 15min
+        stories:
+            main-story:
+                scripts:
+                    - main-script
+    "#;
+
+    #[test]
+    fn parses_event_triggers() {
+        let scenario = parse_sdl(TRIGGER_SDL).unwrap();
+        let events = scenario.events.unwrap();
+        assert_eq!(events["breach"].trigger_type(), TriggerType::Conditions);
+        assert_eq!(events["breach"].trigger_delay(), 300);
+        assert!(events["breach"].are_conditions_met(|condition| condition == "condition-2"));
+        assert_eq!(
+            events["ransom-note"].triggered_after(),
+            Some(&"breach".to_string())
+        );
+        assert_eq!(events["ransom-note"].trigger_repeat(), Some(1800));
+    }
+
+    #[test]
+    fn timeline_follows_event_triggers() {
+        let timeline = parse_sdl(TRIGGER_SDL).unwrap().timeline().unwrap();
+        insta::assert_yaml_snapshot!(timeline.entries);
+    }
+
+    #[test]
+    #[should_panic(expected = "Event Trigger Delay can not be defined for Schedule triggers")]
+    fn fails_on_delay_for_schedule_trigger() {
+        let sdl = TRIGGER_SDL.replace("type: conditions", "type: schedule");
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Event Trigger of type Conditions requires Event Conditions")]
+    fn fails_on_conditions_trigger_without_conditions() {
+        let sdl = TRIGGER_SDL.replace(
+            "                conditions:\n                    - condition-1\n                    - condition-2\n",
+            "",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Cyclic reference detected between Event Triggers")]
+    fn fails_on_cyclic_event_triggers() {
+        let sdl = TRIGGER_SDL
+            .replace(
+                "        events:\n            breach:\n",
+                "        events:\n            kickoff:\n                injects:\n                    - my-cool-inject\n            breach:\n",
+            )
+            .replace("                    breach: 15min\n", "                    kickoff: 15min\n");
+        assert!(parse_sdl(&sdl).is_ok());
+
+        let sdl = sdl.replace(
+            "                trigger:\n                    type: conditions\n                    mode: any\n",
+            "                trigger:\n                    type: after\n                    event: ransom-note\n",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Event \"ransom-note\" is triggered after Event \"breach\" and can not be scheduled in Script \"main-script\""
+    )]
+    fn fails_on_scheduling_after_triggered_event() {
+        let sdl = TRIGGER_SDL.replace(
+            "                    breach: 15min\n",
+            "                    breach: 15min\n                    ransom-note: 1h\n",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
 }
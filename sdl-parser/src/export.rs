@@ -0,0 +1,270 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helpers::{escape_csv_field, escape_ical_text, fold_ical_line},
+    Scenario,
+};
+
+const SCHEDULE_COLUMNS: [&str; 10] = [
+    "Start",
+    "Wall offset",
+    "Story",
+    "Script",
+    "Event",
+    "Name",
+    "Description",
+    "Injects",
+    "From entities",
+    "To entities",
+];
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleEntry {
+    pub timestamp: Option<DateTime<FixedOffset>>,
+    pub wall_offset: f64,
+    pub story: String,
+    pub script: String,
+    pub event: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub injects: Vec<String>,
+    pub from_entities: Vec<String>,
+    pub to_entities: Vec<String>,
+}
+
+impl Scenario {
+    pub fn schedule_entries(&self) -> Result<Vec<ScheduleEntry>> {
+        let timeline = self.timeline()?;
+        let mut schedule_entries = vec![];
+        for entry in timeline.entries {
+            let event = self
+                .events
+                .as_ref()
+                .and_then(|events| events.get(&entry.event));
+            let mut from_entities = vec![];
+            let mut to_entities = vec![];
+            for inject_name in entry.injects.iter() {
+                let Some(inject) = self
+                    .injects
+                    .as_ref()
+                    .and_then(|injects| injects.get(inject_name))
+                else {
+                    continue;
+                };
+                from_entities.extend(inject.from_entity.iter().cloned());
+                to_entities.extend(inject.to_entities.iter().flatten().cloned());
+            }
+            from_entities.sort();
+            from_entities.dedup();
+            to_entities.sort();
+            to_entities.dedup();
+
+            schedule_entries.push(ScheduleEntry {
+                timestamp: entry.timestamp,
+                wall_offset: entry.wall_offset,
+                story: entry.story,
+                script: entry.script,
+                event: entry.event,
+                name: event.and_then(|event| event.name.to_owned()),
+                description: event.and_then(|event| event.description.to_owned()),
+                injects: entry.injects,
+                from_entities,
+                to_entities,
+            });
+        }
+        Ok(schedule_entries)
+    }
+
+    pub fn schedule_to_csv(&self) -> Result<String> {
+        let mut csv = format!("{}\r\n", SCHEDULE_COLUMNS.join(","));
+        for entry in self.schedule_entries()? {
+            let fields = [
+                entry
+                    .timestamp
+                    .map(|timestamp| timestamp.to_rfc3339())
+                    .unwrap_or_default(),
+                entry.wall_offset.to_string(),
+                entry.story,
+                entry.script,
+                entry.event,
+                entry.name.unwrap_or_default(),
+                entry.description.unwrap_or_default(),
+                entry.injects.join(", "),
+                entry.from_entities.join(", "),
+                entry.to_entities.join(", "),
+            ]
+            .iter()
+            .map(|field| escape_csv_field(field))
+            .collect::<Vec<String>>();
+            csv.push_str(&format!("{}\r\n", fields.join(",")));
+        }
+        Ok(csv)
+    }
+
+    pub fn schedule_to_ics(&self) -> Result<String> {
+        let mut ics = String::new();
+        for line in [
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//Open Cyber Range//SDL Parser//EN",
+            "CALSCALE:GREGORIAN",
+        ] {
+            ics.push_str(&fold_ical_line(line));
+        }
+        ics.push_str(&fold_ical_line(&format!(
+            "X-WR-CALNAME:{}",
+            escape_ical_text(&self.name)
+        )));
+
+        let generated_at = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        for entry in self.schedule_entries()? {
+            let timestamp = entry.timestamp.ok_or_else(|| {
+                anyhow!(
+                    "Event \"{}\" of Story \"{}\" has no Schedule to export it to a calendar",
+                    entry.event,
+                    entry.story
+                )
+            })?;
+            let timestamp = timestamp
+                .with_timezone(&Utc)
+                .format("%Y%m%dT%H%M%SZ")
+                .to_string();
+
+            let mut description = vec![];
+            if let Some(event_description) = &entry.description {
+                description.push(event_description.to_owned());
+            }
+            description.push(format!("Story: {}", entry.story));
+            description.push(format!("Script: {}", entry.script));
+            if !entry.injects.is_empty() {
+                description.push(format!("Injects: {}", entry.injects.join(", ")));
+            }
+            if !entry.from_entities.is_empty() {
+                description.push(format!("From: {}", entry.from_entities.join(", ")));
+            }
+            if !entry.to_entities.is_empty() {
+                description.push(format!("To: {}", entry.to_entities.join(", ")));
+            }
+
+            for line in [
+                "BEGIN:VEVENT".to_string(),
+                format!(
+                    "UID:{}",
+                    escape_ical_text(&format!(
+                        "{}.{}.{}.{}@{}",
+                        entry.story, entry.script, entry.event, entry.wall_offset, self.name
+                    ))
+                ),
+                format!("DTSTAMP:{generated_at}"),
+                format!("DTSTART:{timestamp}"),
+                format!(
+                    "SUMMARY:{}",
+                    escape_ical_text(entry.name.as_ref().unwrap_or(&entry.event))
+                ),
+                format!("DESCRIPTION:{}", escape_ical_text(&description.join("\n"))),
+                "END:VEVENT".to_string(),
+            ] {
+                ics.push_str(&fold_ical_line(&line));
+            }
+        }
+        ics.push_str(&fold_ical_line("END:VCALENDAR"));
+        Ok(ics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_sdl;
+    use std::collections::HashSet;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        schedule:
+            start: 2024-05-06T09:00:00+03:00
+        conditions:
+            condition-1:
+                command: executable/path.sh
+                interval: 30
+        entities:
+            white-team:
+                role: White
+            blue-team:
+                role: Blue
+        injects:
+            phishing-email:
+                source: inject-package
+                from-entity: white-team
+                to-entities:
+                    - blue-team
+        events:
+            phishing-wave:
+                name: Phishing wave
+                description: Mass phishing campaign, targeting finance; expect reports
+                conditions:
+                    - condition-1
+                injects:
+                    - phishing-email
+        scripts:
+            main-script:
+                start-time: 0
+                end-time: 8h
+                speed: 1
+                events:
+                    phishing-wave: 1h 30min
+        stories:
+            main-story:
+                scripts:
+                    - main-script
+    "#;
+
+    #[test]
+    fn schedule_is_exported_as_ics() {
+        let ics = parse_sdl(SDL).unwrap().schedule_to_ics().unwrap();
+        assert!(ics.lines().all(|line| line.len() <= 76));
+        let ics = ics
+            .lines()
+            .map(|line| match line.strip_prefix("DTSTAMP:") {
+                Some(generated_at) if generated_at.len() == 16 => "DTSTAMP:[generated]",
+                _ => line,
+            })
+            .collect::<Vec<&str>>()
+            .join("\n");
+        insta::assert_snapshot!(ics);
+    }
+
+    #[test]
+    fn schedule_is_exported_as_csv() {
+        let csv = parse_sdl(SDL).unwrap().schedule_to_csv().unwrap();
+        insta::assert_snapshot!(csv);
+    }
+
+    #[test]
+    fn repeated_event_occurrences_have_unique_ics_uids() {
+        let sdl = SDL.replace(
+            "                injects:\n                    - phishing-email\n",
+            "                injects:\n                    - phishing-email\n                trigger:\n                    repeat: 1h\n",
+        );
+        let ics = parse_sdl(&sdl).unwrap().schedule_to_ics().unwrap();
+        let uids = ics
+            .lines()
+            .filter(|line| line.starts_with("UID:"))
+            .collect::<Vec<&str>>();
+        let unique_uids = uids.iter().collect::<HashSet<_>>();
+        assert_eq!(uids.len(), 7);
+        assert_eq!(unique_uids.len(), uids.len());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Event \"phishing-wave\" of Story \"main-story\" has no Schedule to export it to a calendar"
+    )]
+    fn ics_export_requires_schedule() {
+        let sdl = SDL.replace(
+            "        schedule:\n            start: 2024-05-06T09:00:00+03:00\n",
+            "",
+        );
+        parse_sdl(&sdl).unwrap().schedule_to_ics().unwrap();
+    }
+}
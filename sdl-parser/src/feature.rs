@@ -1,6 +1,8 @@
 use crate::{
     common::{HelperSource, Source},
+    environment::{Environment, HelperEnvironment},
     helpers::Connection,
+    operating_system::OsFamily,
     vulnerability::Vulnerability,
     Formalize,
 };
@@ -42,8 +44,23 @@ pub struct Feature {
     pub destination: Option<String>,
     #[serde(alias = "Description", alias = "DESCRIPTION")]
     pub description: Option<String>,
-    #[serde(alias = "Environment", alias = "ENVIRONMENT")]
-    pub environment: Option<Vec<String>>,
+    #[serde(
+        default,
+        rename = "environment",
+        alias = "Environment",
+        alias = "ENVIRONMENT",
+        skip_serializing
+    )]
+    _environment_helper: Option<HelperEnvironment>,
+    #[serde(default, skip_deserializing)]
+    pub environment: Option<Environment>,
+    #[serde(
+        default,
+        rename = "supported-os",
+        alias = "Supported-os",
+        alias = "SUPPORTED-OS"
+    )]
+    pub supported_os: Option<Vec<OsFamily>>,
 }
 
 impl Connection<Vulnerability> for (&String, &Feature) {
@@ -74,6 +91,13 @@ impl Connection<Vulnerability> for (&String, &Feature) {
 
 pub type Features = HashMap<String, Feature>;
 
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct PlannedFeature {
+    pub feature_name: String,
+    pub role_name: String,
+    pub username: String,
+}
+
 impl Formalize for Feature {
     fn formalize(&mut self) -> Result<()> {
         if let Some(helper_source) = &self._source_helper {
@@ -81,6 +105,9 @@ impl Formalize for Feature {
         } else {
             return Err(anyhow!("Feature missing Source field"));
         }
+        if let Some(environment_helper) = &self._environment_helper {
+            self.environment = Some(environment_helper.to_owned().try_into()?);
+        }
         Ok(())
     }
 }
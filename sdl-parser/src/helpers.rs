@@ -1,5 +1,125 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 pub trait Connection<T> {
     fn validate_connections(&self, potential_connections: &Option<Vec<String>>) -> Result<()>;
 }
+
+pub enum TemplatePart {
+    Literal(String),
+    Reference(String),
+}
+
+/// Splits a templated value into literals and `${reference}` parts. In every templated
+/// field `$$` is an escaped `$`, so `$${reference}` is the literal text `${reference}`.
+/// Without `is_reference` every `${...}` must be a closed, non-empty reference; with it,
+/// any `${` it does not accept is kept as literal text.
+pub fn parse_template(
+    template: &str,
+    is_reference: Option<fn(&str) -> bool>,
+) -> Result<Vec<TemplatePart>> {
+    let mut parts = vec![];
+    let mut literal = String::new();
+    let mut rest = template;
+
+    while let Some(dollar_index) = rest.find('$') {
+        literal.push_str(&rest[..dollar_index]);
+        rest = &rest[dollar_index + 1..];
+        if let Some(escaped_rest) = rest.strip_prefix('$') {
+            literal.push('$');
+            rest = escaped_rest;
+            continue;
+        }
+        let Some(reference_start) = rest.strip_prefix('{') else {
+            literal.push('$');
+            continue;
+        };
+        let reference = reference_start.find('}').map(|end_index| {
+            (
+                reference_start[..end_index].trim(),
+                &reference_start[end_index + 1..],
+            )
+        });
+        match (reference, is_reference) {
+            (Some((reference, reference_rest)), Some(is_reference)) if is_reference(reference) => {
+                parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                parts.push(TemplatePart::Reference(reference.to_owned()));
+                rest = reference_rest;
+            }
+            (Some((reference, reference_rest)), None) if !reference.is_empty() => {
+                parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                parts.push(TemplatePart::Reference(reference.to_owned()));
+                rest = reference_rest;
+            }
+            (_, Some(_)) => literal.push('$'),
+            (None, None) => {
+                return Err(anyhow!("Template \"{template}\" has an unclosed reference"))
+            }
+            (Some(_), None) => {
+                return Err(anyhow!("Template \"{template}\" has an empty reference"))
+            }
+        }
+    }
+    literal.push_str(rest);
+    parts.push(TemplatePart::Literal(literal));
+
+    Ok(parts)
+}
+
+pub fn template_references(template: &str) -> Result<Vec<String>> {
+    Ok(parse_template(template, None)?
+        .into_iter()
+        .filter_map(|part| match part {
+            TemplatePart::Reference(reference) => Some(reference),
+            TemplatePart::Literal(_) => None,
+        })
+        .collect())
+}
+
+pub fn render_template<F>(template: &str, resolve: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    parse_template(template, None)?
+        .into_iter()
+        .map(|part| match part {
+            TemplatePart::Literal(literal) => Ok(literal),
+            TemplatePart::Reference(reference) => resolve(&reference)
+                .ok_or_else(|| anyhow!("Template value for \"{reference}\" is not known")),
+        })
+        .collect()
+}
+
+pub fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+pub fn escape_markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+pub fn escape_ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+pub fn fold_ical_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut line_length = 0;
+    for character in line.chars() {
+        if line_length + character.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            line_length = 1;
+        }
+        folded.push(character);
+        line_length += character.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
@@ -17,6 +17,15 @@ pub struct InfraNode {
     pub links: Option<Vec<String>>,
     #[serde(default, alias = "Dependencies", alias = "DEPENDENCIES")]
     pub dependencies: Option<Vec<String>>,
+    #[serde(default, alias = "Affinity", alias = "AFFINITY")]
+    pub affinity: Option<Vec<String>>,
+    #[serde(
+        default,
+        rename = "anti-affinity",
+        alias = "Anti-affinity",
+        alias = "ANTI-AFFINITY"
+    )]
+    pub anti_affinity: Option<Vec<String>>,
     #[serde(alias = "Description", alias = "DESCRIPTION")]
     pub description: Option<String>,
 }
@@ -56,6 +65,16 @@ impl Formalize for InfraNode {
                 "Infrastructure Count field can not be less than {MINIMUM_NODE_COUNT}"
             ));
         }
+        if let (Some(affinity), Some(anti_affinity)) = (&self.affinity, &self.anti_affinity) {
+            if let Some(node_name) = affinity
+                .iter()
+                .find(|node_name| anti_affinity.contains(node_name))
+            {
+                return Err(anyhow!(
+                    "Infrastructure entry \"{node_name}\" can not be under both Affinity and Anti-affinity"
+                ));
+            }
+        }
         Ok(())
     }
 }
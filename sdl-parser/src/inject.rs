@@ -4,12 +4,187 @@ use std::collections::HashMap;
 
 use crate::{
     common::{HelperSource, Source},
-    entity::Entity,
-    helpers::Connection,
+    entity::{Entity, Flatten},
+    environment::{Environment, HelperEnvironment},
+    helpers::{render_template, template_references, Connection},
+    node::VM,
+    operating_system::OsFamily,
+    script::deserialize_string_to_u64,
     training_learning_objective::TrainingLearningObjective,
-    Formalize,
+    Formalize, Scenario,
 };
 
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum ChannelType {
+    #[serde(alias = "email", alias = "EMAIL")]
+    Email,
+    #[serde(alias = "chat", alias = "CHAT")]
+    Chat,
+    #[serde(rename = "Phone-call", alias = "phone-call", alias = "PHONE-CALL")]
+    PhoneCall,
+    #[serde(alias = "ticket", alias = "TICKET")]
+    Ticket,
+    #[serde(rename = "File-drop", alias = "file-drop", alias = "FILE-DROP")]
+    FileDrop,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Channel {
+    #[serde(rename = "type", alias = "Type", alias = "TYPE")]
+    pub channel_type: ChannelType,
+    #[serde(default, alias = "Node", alias = "NODE")]
+    pub node: Option<String>,
+    #[serde(default, alias = "Path", alias = "PATH")]
+    pub path: Option<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum HelperChannel {
+    Channel(Channel),
+    ShortChannel(ChannelType),
+}
+
+impl From<HelperChannel> for Channel {
+    fn from(helper_channel: HelperChannel) -> Self {
+        match helper_channel {
+            HelperChannel::Channel(channel) => channel,
+            HelperChannel::ShortChannel(channel_type) => Channel {
+                channel_type,
+                node: None,
+                path: None,
+            },
+        }
+    }
+}
+
+impl Formalize for Channel {
+    fn formalize(&mut self) -> Result<()> {
+        match self.channel_type {
+            ChannelType::FileDrop if self.node.is_none() || self.path.is_none() => Err(anyhow!(
+                "Inject Channel of type File-drop must have Node and Path defined"
+            )),
+            ChannelType::FileDrop => Ok(()),
+            _ if self.node.is_some() || self.path.is_some() => Err(anyhow!(
+                "Inject Channel Node and Path can only be defined for File-drop"
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Content {
+    #[serde(default, alias = "Subject", alias = "SUBJECT")]
+    pub subject: Option<String>,
+    #[serde(default, alias = "Body", alias = "BODY")]
+    pub body: Option<String>,
+    #[serde(
+        default,
+        rename = "attachments",
+        alias = "Attachments",
+        alias = "ATTACHMENTS",
+        skip_serializing
+    )]
+    attachments_helper: Option<Vec<HelperSource>>,
+    #[serde(default, skip_deserializing)]
+    pub attachments: Option<Vec<Source>>,
+}
+
+impl Formalize for Content {
+    fn formalize(&mut self) -> Result<()> {
+        if self.subject.is_none() && self.body.is_none() && self.attachments_helper.is_none() {
+            return Err(anyhow!(
+                "Inject Content must have Subject, Body or Attachments defined"
+            ));
+        }
+        if let Some(attachments_helper) = &self.attachments_helper {
+            self.attachments = Some(
+                attachments_helper
+                    .iter()
+                    .cloned()
+                    .map(Source::from)
+                    .collect(),
+            );
+        }
+        for template in self.subject.iter().chain(self.body.iter()) {
+            for reference in template_references(template)? {
+                if !matches!(
+                    reference.split_once('.'),
+                    Some((
+                        "from-entity" | "to-entity",
+                        "name" | "mission" | "description"
+                    ))
+                ) {
+                    return Err(anyhow!(
+                        "Inject Content reference \"{reference}\" is not a known Entity field"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum ResponseType {
+    #[serde(alias = "manual", alias = "MANUAL")]
+    Manual,
+    #[serde(alias = "automated", alias = "AUTOMATED")]
+    Automated,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct ExpectedResponse {
+    #[serde(rename = "type", alias = "Type", alias = "TYPE")]
+    pub response_type: ResponseType,
+    #[serde(
+        deserialize_with = "deserialize_string_to_u64",
+        alias = "Deadline",
+        alias = "DEADLINE"
+    )]
+    pub deadline: u64,
+    #[serde(default, alias = "Metric", alias = "METRIC")]
+    pub metric: Option<String>,
+    #[serde(default, alias = "TLO", alias = "Tlo")]
+    pub tlo: Option<String>,
+    #[serde(default, alias = "Condition", alias = "CONDITION")]
+    pub condition: Option<String>,
+    #[serde(alias = "Description", alias = "DESCRIPTION")]
+    pub description: Option<String>,
+}
+
+impl Formalize for ExpectedResponse {
+    fn formalize(&mut self) -> Result<()> {
+        if self.deadline == 0 {
+            return Err(anyhow!(
+                "Inject Expected-response Deadline must be greater than 0"
+            ));
+        }
+        if self.metric.is_none() && self.tlo.is_none() {
+            return Err(anyhow!(
+                "Inject Expected-response must have a Metric or a TLO defined"
+            ));
+        }
+        match (self.response_type, &self.condition) {
+            (ResponseType::Automated, None) => Err(anyhow!(
+                "Inject Expected-response of type Automated must have a Condition defined"
+            )),
+            (ResponseType::Manual, Some(_)) => Err(anyhow!(
+                "Inject Expected-response of type Manual can not have a Condition defined"
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct RenderedContent {
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    pub attachments: Vec<Source>,
+}
+
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
 pub struct Inject {
     #[serde(default, alias = "Name", alias = "NAME")]
@@ -32,8 +207,42 @@ pub struct Inject {
     pub tlos: Option<Vec<String>>,
     #[serde(alias = "Description", alias = "DESCRIPTION")]
     pub description: Option<String>,
-    #[serde(alias = "Environment", alias = "ENVIRONMENT")]
-    pub environment: Option<Vec<String>>,
+    #[serde(
+        default,
+        rename = "environment",
+        alias = "Environment",
+        alias = "ENVIRONMENT",
+        skip_serializing
+    )]
+    environment_helper: Option<HelperEnvironment>,
+    #[serde(default, skip_deserializing)]
+    pub environment: Option<Environment>,
+    #[serde(
+        default,
+        rename = "supported-os",
+        alias = "Supported-os",
+        alias = "SUPPORTED-OS"
+    )]
+    pub supported_os: Option<Vec<OsFamily>>,
+    #[serde(
+        default,
+        rename = "channel",
+        alias = "Channel",
+        alias = "CHANNEL",
+        skip_serializing
+    )]
+    channel_helper: Option<HelperChannel>,
+    #[serde(default, skip_deserializing)]
+    pub channel: Option<Channel>,
+    #[serde(default, alias = "Content", alias = "CONTENT")]
+    pub content: Option<Content>,
+    #[serde(
+        default,
+        rename = "expected-responses",
+        alias = "Expected-responses",
+        alias = "EXPECTED-RESPONSES"
+    )]
+    pub expected_responses: Option<HashMap<String, ExpectedResponse>>,
 }
 
 pub type Injects = HashMap<String, Inject>;
@@ -51,10 +260,100 @@ impl Formalize for Inject {
         } else if let Some(source_helper) = &self.source_helper {
             self.source = Some(source_helper.to_owned().into());
         }
+        if let Some(environment_helper) = &self.environment_helper {
+            self.environment = Some(environment_helper.to_owned().try_into()?);
+        }
+        if let Some(channel_helper) = &self.channel_helper {
+            let mut channel: Channel = channel_helper.to_owned().into();
+            channel.formalize()?;
+            self.channel = Some(channel);
+        }
+        if let Some(expected_responses) = &mut self.expected_responses {
+            for expected_response in expected_responses.values_mut() {
+                expected_response.formalize()?;
+            }
+        }
+        if let Some(content) = &mut self.content {
+            content.formalize()?;
+            let references = content
+                .subject
+                .iter()
+                .chain(content.body.iter())
+                .map(|template| template_references(template))
+                .collect::<Result<Vec<Vec<String>>>>()?;
+            if self.from_entity.is_none()
+                && references
+                    .iter()
+                    .flatten()
+                    .any(|reference| reference.contains("entity."))
+            {
+                return Err(anyhow!(
+                    "Inject Content references Entities but `from-entity` and `to-entities` are not declared"
+                ));
+            }
+        }
         Ok(())
     }
 }
 
+impl Scenario {
+    pub fn render_inject_content(
+        &self,
+        inject_name: &str,
+        to_entity_name: &str,
+    ) -> Result<RenderedContent> {
+        let inject = self
+            .injects
+            .as_ref()
+            .and_then(|injects| injects.get(inject_name))
+            .ok_or_else(|| anyhow!("Inject \"{inject_name}\" not found under Scenario Injects"))?;
+        if !inject
+            .to_entities
+            .iter()
+            .flatten()
+            .any(|entity_name| entity_name == to_entity_name)
+        {
+            return Err(anyhow!(
+                "Entity \"{to_entity_name}\" is not a recipient of Inject \"{inject_name}\""
+            ));
+        }
+        let Some(content) = &inject.content else {
+            return Err(anyhow!("Inject \"{inject_name}\" has no Content"));
+        };
+
+        let entities = self
+            .entities
+            .as_ref()
+            .map(|entities| entities.flatten())
+            .unwrap_or_default();
+        let entity_field = |entity_name: Option<&String>, field: &str| {
+            let entity_name = entity_name?;
+            let entity = entities.get(entity_name)?;
+            match field {
+                "name" => Some(entity.name.to_owned().unwrap_or(entity_name.to_owned())),
+                "mission" => entity.mission.to_owned(),
+                "description" => entity.description.to_owned(),
+                _ => None,
+            }
+        };
+        let to_entity_name = to_entity_name.to_owned();
+        let render = |template: &String| {
+            render_template(template, |reference| match reference.split_once('.') {
+                Some(("from-entity", field)) => entity_field(inject.from_entity.as_ref(), field),
+                Some(("to-entity", field)) => entity_field(Some(&to_entity_name), field),
+                _ => None,
+            })
+            .map_err(|error| anyhow!("Inject \"{inject_name}\" Content: {error}"))
+        };
+
+        Ok(RenderedContent {
+            subject: content.subject.as_ref().map(render).transpose()?,
+            body: content.body.as_ref().map(render).transpose()?,
+            attachments: content.attachments.to_owned().unwrap_or_default(),
+        })
+    }
+}
+
 impl Connection<Entity> for (&String, &Inject) {
     fn validate_connections(&self, potential_entity_names: &Option<Vec<String>>) -> Result<()> {
         if self.1.to_entities.is_some() && potential_entity_names.is_none()
@@ -89,6 +388,30 @@ impl Connection<Entity> for (&String, &Inject) {
     }
 }
 
+impl Connection<VM> for (&String, &Inject) {
+    fn validate_connections(&self, potential_vm_names: &Option<Vec<String>>) -> Result<()> {
+        if let Some(node_name) = self
+            .1
+            .channel
+            .as_ref()
+            .and_then(|channel| channel.node.as_ref())
+        {
+            if !potential_vm_names
+                .iter()
+                .flatten()
+                .any(|vm_name| vm_name == node_name)
+            {
+                return Err(anyhow!(
+                    "Inject \"{inject_name}\" Channel Node \"{node_name}\" not found under Scenario VMs",
+                    inject_name = self.0
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Connection<TrainingLearningObjective> for (&String, &Inject) {
     fn validate_connections(&self, potential_tlo_names: &Option<Vec<String>>) -> Result<()> {
         if self.1.tlos.is_some() && potential_tlo_names.is_none() {
@@ -117,7 +440,10 @@ impl Connection<TrainingLearningObjective> for (&String, &Inject) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parse_sdl;
+    use crate::{
+        parse_sdl,
+        reference::{ElementId, ElementKind},
+    };
 
     #[test]
     fn parses_sdl_with_injects() {
@@ -425,4 +751,198 @@ mod tests {
             "#;
         parse_sdl(sdl).unwrap();
     }
+
+    const CHANNEL_SDL: &str = r#"
+        name: test-scenario
+        nodes:
+            workstation:
+                type: VM
+                source: windows-10
+                resources:
+                    ram: 2 gib
+                    cpu: 1
+            switch-1:
+                type: Switch
+        entities:
+            white-team:
+                name: Exercise control
+                role: White
+            blue-team:
+                role: Blue
+                mission: Keep the mail service available
+        injects:
+            phishing-email:
+                source: inject-package
+                from-entity: white-team
+                to-entities:
+                    - blue-team
+                channel: email
+                content:
+                    subject: Urgent request from ${from-entity.name}
+                    body: "Dear ${to-entity.name}, remember your mission: ${to-entity.mission}"
+                    attachments:
+                        - invoice-package
+                        - name: macro-document
+                          version: 1.2.0
+            dropped-file:
+                source: inject-package
+                channel:
+                    type: file-drop
+                    node: workstation
+                    path: C:\Users\Public\readme.txt
+    "#;
+
+    #[test]
+    fn renders_inject_content_for_recipient() {
+        let scenario = parse_sdl(CHANNEL_SDL).unwrap();
+        let injects = scenario.injects.as_ref().unwrap();
+        assert_eq!(
+            injects["phishing-email"]
+                .channel
+                .as_ref()
+                .unwrap()
+                .channel_type,
+            ChannelType::Email
+        );
+        let content = scenario
+            .render_inject_content("phishing-email", "blue-team")
+            .unwrap();
+        insta::assert_yaml_snapshot!(content);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Inject \"dropped-file\" Channel Node \"switch-1\" not found under Scenario VMs"
+    )]
+    fn fails_on_file_drop_to_non_vm_node() {
+        let sdl = CHANNEL_SDL.replace("node: workstation", "node: switch-1");
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = r#"Inject "dropped-file" Channel Path "C:\Users\Public\readme.txt" is not a valid path for the Operating System family Linux of VM "workstation""#
+    )]
+    fn fails_on_file_drop_path_of_other_operating_system() {
+        let sdl = CHANNEL_SDL.replace(
+            "                    cpu: 1\n",
+            "                    cpu: 1\n                os:\n                    family: linux\n",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Inject Channel of type File-drop must have Node and Path defined")]
+    fn fails_on_file_drop_without_path() {
+        let sdl = CHANNEL_SDL.replace(
+            "                    path: C:\\Users\\Public\\readme.txt\n",
+            "",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Inject Content reference \"to-entity.role\" is not a known Entity field"
+    )]
+    fn fails_on_unknown_content_reference() {
+        let sdl = CHANNEL_SDL.replace("${to-entity.mission}", "${to-entity.role}");
+        parse_sdl(&sdl).unwrap();
+    }
+
+    const RESPONSE_SDL: &str = r#"
+        name: test-scenario
+        conditions:
+            phish-reported:
+                command: executable/path.sh
+                interval: 30
+        metrics:
+            report-quality:
+                type: MANUAL
+                artifact: true
+                max-score: 10
+            report-speed:
+                type: CONDITIONAL
+                max-score: 10
+                condition: phish-reported
+        evaluations:
+            evaluation-1:
+                metrics:
+                    - report-quality
+                    - report-speed
+                min-score: 50
+        tlos:
+            tlo-1:
+                evaluation: evaluation-1
+        entities:
+            white-team:
+                role: White
+            blue-team:
+                role: Blue
+        injects:
+            phishing-email:
+                source: inject-package
+                from-entity: white-team
+                to-entities:
+                    - blue-team
+                expected-responses:
+                    incident-report:
+                        type: manual
+                        deadline: 1h
+                        metric: report-quality
+                        tlo: tlo-1
+                    mailbox-quarantined:
+                        type: automated
+                        deadline: 15min
+                        metric: report-speed
+                        condition: phish-reported
+    "#;
+
+    #[test]
+    fn parses_expected_responses() {
+        let scenario = parse_sdl(RESPONSE_SDL).unwrap();
+        let responses = scenario.injects.as_ref().unwrap()["phishing-email"]
+            .expected_responses
+            .as_ref()
+            .unwrap();
+        assert_eq!(responses["incident-report"].deadline, 3600);
+        assert_eq!(
+            responses["mailbox-quarantined"].response_type,
+            ResponseType::Automated
+        );
+        assert!(scenario
+            .reference_index()
+            .referenced_by(ElementKind::Metric, "report-speed")
+            .contains(&ElementId::new(ElementKind::Inject, "phishing-email")));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Inject \"phishing-email\" Manual Expected-response \"incident-report\" must be scored by a Manual Metric"
+    )]
+    fn fails_on_manual_response_with_conditional_metric() {
+        let sdl = RESPONSE_SDL.replace("metric: report-quality", "metric: report-speed");
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Inject \"phishing-email\" Expected-response \"mailbox-quarantined\" Condition \"mailbox-check\" not found under Scenario Conditions"
+    )]
+    fn fails_on_missing_response_condition() {
+        let sdl = RESPONSE_SDL.replace(
+            "                        condition: phish-reported\n",
+            "                        condition: mailbox-check\n",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Inject Expected-response of type Automated must have a Condition defined"
+    )]
+    fn fails_on_automated_response_without_condition() {
+        let sdl = RESPONSE_SDL.replace("                        condition: phish-reported\n", "");
+        parse_sdl(&sdl).unwrap();
+    }
 }
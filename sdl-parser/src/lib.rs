@@ -1,9 +1,13 @@
 pub mod common;
 pub mod condition;
 mod constants;
+pub mod credential;
+pub mod diagnostics;
 pub mod entity;
+pub mod environment;
 pub mod evaluation;
 pub mod event;
+pub mod export;
 pub mod feature;
 pub mod goal;
 mod helpers;
@@ -12,8 +16,18 @@ pub mod inject;
 mod library_item;
 pub mod metric;
 pub mod node;
+pub mod operating_system;
+pub mod placement;
+pub mod reachability;
+pub mod reference;
+pub mod rename;
+pub mod schedule;
+pub mod scoring;
 pub mod script;
+pub mod simulate;
 pub mod story;
+pub mod timeline;
+pub mod traceability;
 pub mod training_learning_objective;
 pub mod vulnerability;
 
@@ -23,15 +37,17 @@ use condition::{Condition, Conditions};
 use constants::MAX_LONG_NAME;
 use depper::{Dependencies, DependenciesBuilder};
 use entity::{Entities, Entity, Flatten};
+use environment::{validate_environment_references, Parameters};
 use evaluation::{Evaluation, Evaluations};
 use event::{Event, Events};
-use feature::{Feature, Features};
+use feature::{Feature, Features, PlannedFeature};
 use goal::Goals;
 use infrastructure::{Infrastructure, InfrastructureHelper};
-use inject::{Inject, Injects};
+use inject::{Inject, Injects, ResponseType};
 pub use library_item::LibraryItem;
-use metric::{Metric, Metrics};
-use node::{Node, NodeType, Nodes};
+use metric::{Metric, MetricType, Metrics};
+use node::{Node, NodeType, Nodes, RoleReport, RoleUsage, VM};
+use schedule::Schedule;
 use script::{Script, Scripts};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -49,6 +65,8 @@ pub struct Scenario {
     pub name: String,
     #[serde(default, alias = "Description", alias = "DESCRIPTION")]
     pub description: Option<String>,
+    #[serde(default, alias = "Parameters", alias = "PARAMETERS")]
+    pub parameters: Option<Parameters>,
     #[serde(alias = "Nodes", alias = "NODES")]
     pub nodes: Option<Nodes>,
     #[serde(alias = "Features", alias = "FEATURES")]
@@ -90,6 +108,8 @@ pub struct Scenario {
     pub scripts: Option<Scripts>,
     #[serde(alias = "Stories", alias = "STORIES")]
     pub stories: Option<Stories>,
+    #[serde(default, alias = "Schedule", alias = "SCHEDULE")]
+    pub schedule: Option<Schedule>,
 }
 
 impl Scenario {
@@ -97,6 +117,12 @@ impl Scenario {
         serde_yaml::to_string(&self).map_err(|e| anyhow!("Failed to serialize to yaml: {}", e))
     }
 
+    pub fn to_yaml_redacted(&self) -> Result<String> {
+        let mut scenario = self.clone();
+        scenario.redact_credentials();
+        scenario.to_yaml()
+    }
+
     pub fn from_yaml(yaml: &str) -> Result<Self> {
         let mut schema: Self = serde_yaml::from_str(yaml)
             .map_err(|e| anyhow!("Failed to deserialize from yaml: {}", e))?;
@@ -143,6 +169,61 @@ impl Scenario {
         self.build_a_single_features_dependencies(dependency_builder, node_feature_name)
     }
 
+    pub fn node_feature_plan(&self, node_name: &str) -> Result<Vec<PlannedFeature>> {
+        let node = self
+            .nodes
+            .as_ref()
+            .and_then(|nodes| nodes.get(node_name))
+            .ok_or_else(|| anyhow!("Node \"{node_name}\" not found under Scenario"))?;
+        let NodeType::VM(vm) = &node.type_field else {
+            return Err(anyhow!("Node \"{node_name}\" is not a VM"));
+        };
+
+        let mut node_feature_names = vm.features.keys().cloned().collect::<Vec<String>>();
+        node_feature_names.sort();
+
+        let mut dependency_builder = Dependencies::builder();
+        for feature_name in node_feature_names.iter() {
+            let feature_dependencies = self
+                .features
+                .as_ref()
+                .and_then(|features| features.get(feature_name))
+                .and_then(|feature| feature.dependencies.to_owned())
+                .unwrap_or_default();
+            for dependency_name in feature_dependencies.iter() {
+                if !vm.features.contains_key(dependency_name) {
+                    return Err(anyhow!(
+                        "Feature \"{dependency_name}\" is a dependency of Feature \"{feature_name}\" but it is not attached to VM \"{node_name}\""
+                    ));
+                }
+            }
+            dependency_builder =
+                dependency_builder.add_element(feature_name.to_owned(), feature_dependencies);
+        }
+
+        let mut feature_plan = vec![];
+        for mut tranche in dependency_builder.build()?.generate_tranches()? {
+            tranche.sort();
+            for feature_name in tranche {
+                let role_name = vm.features[&feature_name].to_owned();
+                let username = vm
+                    .roles
+                    .as_ref()
+                    .and_then(|roles| roles.get(&role_name))
+                    .map(|role| role.username.to_owned())
+                    .ok_or_else(|| {
+                        anyhow!("Role {role_name} not found under for Node {node_name}'s roles")
+                    })?;
+                feature_plan.push(PlannedFeature {
+                    feature_name,
+                    role_name,
+                    username,
+                });
+            }
+        }
+        Ok(feature_plan)
+    }
+
     fn build_infrastructure_dependencies(
         &self,
         mut dependency_builder: depper::DependenciesBuilder,
@@ -338,6 +419,28 @@ impl Scenario {
                         ));
                     }
                 }
+
+                let infra_node_affinities = infrastructure
+                    .get(infrastructure_name)
+                    .map(|infra_node| {
+                        let mut affinities = vec![];
+                        if let Some(affinity) = &infra_node.affinity {
+                            affinities.extend(affinity.iter().map(|node_name| node_name.to_owned()));
+                        }
+                        if let Some(anti_affinity) = &infra_node.anti_affinity {
+                            affinities.extend(anti_affinity.iter().map(|node_name| node_name.to_owned()));
+                        }
+                        affinities
+                    })
+                    .unwrap_or_default();
+
+                for affinity in infra_node_affinities {
+                    if !infrastructure.contains_key(&affinity) {
+                        return Err(anyhow!(
+                            "Infrastructure entry \"{affinity}\" does not exist under Infrastructure even though it is an affinity hint for \"{infrastructure_name}\""
+                        ));
+                    }
+                }
                 Ok(())
             })?;
         }
@@ -400,6 +503,41 @@ impl Scenario {
         Ok(())
     }
 
+    fn verify_conditions(&self) -> Result<()> {
+        let Some(conditions) = &self.conditions else {
+            return Ok(());
+        };
+        let condition_names = Some(conditions.keys().cloned().collect::<Vec<String>>());
+
+        let mut dependency_builder = Dependencies::builder();
+        for (condition_name, condition) in conditions.iter() {
+            (condition_name, condition).validate_connections(&condition_names)?;
+            dependency_builder = dependency_builder
+                .add_element(condition_name.to_owned(), condition.referenced_conditions());
+        }
+        dependency_builder
+            .build()
+            .map_err(|_| anyhow!("Cyclic reference detected between composite Conditions"))?;
+
+        if let Some(nodes) = &self.nodes {
+            for (node_name, node) in nodes.iter() {
+                if let NodeType::VM(vm) = &node.type_field {
+                    for condition_name in vm.conditions.keys() {
+                        if conditions
+                            .get(condition_name)
+                            .is_some_and(|condition| condition.is_composite())
+                        {
+                            return Err(anyhow!(
+                                "Composite Condition \"{condition_name}\" can not be attached to VM \"{node_name}\""
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn verify_features(&self) -> Result<()> {
         let vulnerability_names = self
             .vulnerabilities
@@ -469,9 +607,18 @@ impl Scenario {
             .as_ref()
             .map(|tlo_map| tlo_map.keys().cloned().collect::<Vec<String>>());
 
+        let vm_names = self.nodes.as_ref().map(|nodes| {
+            nodes
+                .iter()
+                .filter(|(_, node)| matches!(node.type_field, NodeType::VM(_)))
+                .map(|(node_name, _)| node_name.to_owned())
+                .collect::<Vec<String>>()
+        });
+
         if let Some(injects) = &self.injects {
             for named_inject in injects.iter() {
                 Connection::<Entity>::validate_connections(&named_inject, &entity_names)?;
+                Connection::<VM>::validate_connections(&named_inject, &vm_names)?;
                 Connection::<TrainingLearningObjective>::validate_connections(
                     &named_inject,
                     &tlo_names,
@@ -481,6 +628,66 @@ impl Scenario {
         Ok(())
     }
 
+    fn verify_expected_responses(&self) -> Result<()> {
+        let Some(injects) = &self.injects else {
+            return Ok(());
+        };
+        for (inject_name, inject) in injects.iter() {
+            for (response_name, response) in inject.expected_responses.iter().flatten() {
+                if let Some(tlo_name) = &response.tlo {
+                    if !self
+                        .tlos
+                        .as_ref()
+                        .is_some_and(|tlos| tlos.contains_key(tlo_name))
+                    {
+                        return Err(anyhow!(
+                            "Inject \"{inject_name}\" Expected-response \"{response_name}\" TLO \"{tlo_name}\" not found under Scenario TLOs"
+                        ));
+                    }
+                }
+                if let Some(condition_name) = &response.condition {
+                    if !self
+                        .conditions
+                        .as_ref()
+                        .is_some_and(|conditions| conditions.contains_key(condition_name))
+                    {
+                        return Err(anyhow!(
+                            "Inject \"{inject_name}\" Expected-response \"{response_name}\" Condition \"{condition_name}\" not found under Scenario Conditions"
+                        ));
+                    }
+                }
+                let Some(metric_name) = &response.metric else {
+                    continue;
+                };
+                let metric = self
+                    .metrics
+                    .as_ref()
+                    .and_then(|metrics| metrics.get(metric_name))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Inject \"{inject_name}\" Expected-response \"{response_name}\" Metric \"{metric_name}\" not found under Scenario Metrics"
+                        )
+                    })?;
+                match (response.response_type, &metric.metric_type) {
+                    (ResponseType::Manual, MetricType::Manual) => {}
+                    (ResponseType::Automated, MetricType::Conditional)
+                        if metric.condition == response.condition => {}
+                    (ResponseType::Manual, _) => {
+                        return Err(anyhow!(
+                            "Inject \"{inject_name}\" Manual Expected-response \"{response_name}\" must be scored by a Manual Metric"
+                        ));
+                    }
+                    (ResponseType::Automated, _) => {
+                        return Err(anyhow!(
+                            "Inject \"{inject_name}\" Automated Expected-response \"{response_name}\" must be scored by a Conditional Metric with the same Condition"
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn verify_roles(&self) -> Result<()> {
         if let Some(nodes) = &self.nodes {
             let all_entity_names = self
@@ -507,6 +714,11 @@ impl Scenario {
                             &(node_name, &vm.roles),
                             &Some(condition_roles),
                         )?;
+                        let inject_roles = vm.injects.values().cloned().collect::<Vec<String>>();
+                        Connection::<Node>::validate_connections(
+                            &(node_name, &vm.roles),
+                            &Some(inject_roles),
+                        )?;
                     }
                     _ => continue,
                 }
@@ -515,6 +727,134 @@ impl Scenario {
         Ok(())
     }
 
+    pub fn role_report(&self) -> RoleReport {
+        let mut role_report = RoleReport::new();
+        if let Some(nodes) = &self.nodes {
+            for (node_name, node) in nodes {
+                let NodeType::VM(vm) = &node.type_field else {
+                    continue;
+                };
+                let Some(roles) = &vm.roles else {
+                    continue;
+                };
+                let node_role_usages = roles
+                    .iter()
+                    .map(|(role_name, role)| {
+                        let role_usage = RoleUsage {
+                            username: role.username.to_owned(),
+                            entities: role.entities.to_owned(),
+                            features: VM::names_running_as(&vm.features, role_name),
+                            conditions: VM::names_running_as(&vm.conditions, role_name),
+                            injects: VM::names_running_as(&vm.injects, role_name),
+                        };
+                        (role_name.to_owned(), role_usage)
+                    })
+                    .collect();
+                role_report.insert(node_name.to_owned(), node_role_usages);
+            }
+        }
+        role_report
+    }
+
+    fn verify_operating_systems(&self) -> Result<()> {
+        if let Some(nodes) = &self.nodes {
+            for (node_name, node) in nodes {
+                let NodeType::VM(vm) = &node.type_field else {
+                    continue;
+                };
+                let Some(operating_system) = &vm.os else {
+                    continue;
+                };
+                let os_family = &operating_system.family;
+
+                if let Some(features) = &self.features {
+                    for feature_name in vm.features.keys() {
+                        if let Some(feature) = features.get(feature_name) {
+                            if !operating_system.is_supported_by(&feature.supported_os) {
+                                return Err(anyhow!(
+                                    "Feature \"{feature_name}\" does not support the Operating System family {os_family:?} of VM \"{node_name}\""
+                                ));
+                            }
+                            if let Some(destination) = &feature.destination {
+                                if !os_family.accepts_path(destination) {
+                                    return Err(anyhow!(
+                                        "Feature \"{feature_name}\" Destination \"{destination}\" is not a valid path for the Operating System family {os_family:?} of VM \"{node_name}\""
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+                if let Some(conditions) = &self.conditions {
+                    for condition_name in vm.conditions.keys() {
+                        if let Some(condition) = conditions.get(condition_name) {
+                            if !operating_system.is_supported_by(&condition.supported_os) {
+                                return Err(anyhow!(
+                                    "Condition \"{condition_name}\" does not support the Operating System family {os_family:?} of VM \"{node_name}\""
+                                ));
+                            }
+                        }
+                    }
+                }
+                if let Some(injects) = &self.injects {
+                    for inject_name in vm.injects.keys() {
+                        if let Some(inject) = injects.get(inject_name) {
+                            if !operating_system.is_supported_by(&inject.supported_os) {
+                                return Err(anyhow!(
+                                    "Inject \"{inject_name}\" does not support the Operating System family {os_family:?} of VM \"{node_name}\""
+                                ));
+                            }
+                        }
+                    }
+                    for (inject_name, inject) in injects {
+                        let Some(channel) = &inject.channel else {
+                            continue;
+                        };
+                        if channel.node.as_ref() != Some(node_name) {
+                            continue;
+                        }
+                        if let Some(path) = &channel.path {
+                            if !os_family.accepts_path(path) {
+                                return Err(anyhow!(
+                                    "Inject \"{inject_name}\" Channel Path \"{path}\" is not a valid path for the Operating System family {os_family:?} of VM \"{node_name}\""
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_environments(&self) -> Result<()> {
+        if let Some(features) = &self.features {
+            for (feature_name, feature) in features {
+                if let Some(environment) = &feature.environment {
+                    validate_environment_references(environment, &self.parameters)
+                        .map_err(|error| anyhow!("Feature \"{feature_name}\" {error}"))?;
+                }
+            }
+        }
+        if let Some(conditions) = &self.conditions {
+            for (condition_name, condition) in conditions {
+                if let Some(environment) = &condition.environment {
+                    validate_environment_references(environment, &self.parameters)
+                        .map_err(|error| anyhow!("Condition \"{condition_name}\" {error}"))?;
+                }
+            }
+        }
+        if let Some(injects) = &self.injects {
+            for (inject_name, inject) in injects {
+                if let Some(environment) = &inject.environment {
+                    validate_environment_references(environment, &self.parameters)
+                        .map_err(|error| anyhow!("Inject \"{inject_name}\" {error}"))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn verify_events(&self) -> Result<()> {
         let condition_names = self
             .conditions
@@ -525,10 +865,42 @@ impl Scenario {
             .as_ref()
             .map(|tlo_map| tlo_map.keys().cloned().collect::<Vec<String>>());
 
-        if let Some(events) = &self.events {
-            for named_event in events.iter() {
-                Connection::<Condition>::validate_connections(&named_event, &condition_names)?;
-                Connection::<Inject>::validate_connections(&named_event, &inject_names)?;
+        let Some(events) = &self.events else {
+            return Ok(());
+        };
+        let event_names = Some(events.keys().cloned().collect::<Vec<String>>());
+
+        let mut dependency_builder = Dependencies::builder();
+        for named_event in events.iter() {
+            Connection::<Condition>::validate_connections(&named_event, &condition_names)?;
+            Connection::<Inject>::validate_connections(&named_event, &inject_names)?;
+            Connection::<Event>::validate_connections(&named_event, &event_names)?;
+            dependency_builder = dependency_builder.add_element(
+                named_event.0.to_owned(),
+                named_event
+                    .1
+                    .triggered_after()
+                    .cloned()
+                    .into_iter()
+                    .collect(),
+            );
+        }
+        dependency_builder
+            .build()
+            .map_err(|_| anyhow!("Cyclic reference detected between Event Triggers"))?;
+
+        if let Some(scripts) = &self.scripts {
+            for (script_name, script) in scripts.iter() {
+                for event_name in script.events.keys() {
+                    if let Some(trigger_event_name) = events
+                        .get(event_name)
+                        .and_then(|event| event.triggered_after())
+                    {
+                        return Err(anyhow!(
+                            "Event \"{event_name}\" is triggered after Event \"{trigger_event_name}\" and can not be scheduled in Script \"{script_name}\""
+                        ));
+                    }
+                }
             }
         }
         Ok(())
@@ -672,6 +1044,10 @@ impl Formalize for Scenario {
             self.stories = Some(stories);
         }
 
+        if let Some(schedule) = &mut self.schedule {
+            schedule.formalize()?;
+        }
+
         self.verify_entities()?;
         self.verify_goals()?;
         self.verify_nodes()?;
@@ -680,10 +1056,14 @@ impl Formalize for Scenario {
         self.verify_switch_counts()?;
         self.verify_features()?;
         self.verify_dependencies()?;
+        self.verify_conditions()?;
         self.verify_metrics()?;
         self.verify_training_learning_objectives()?;
         self.verify_roles()?;
+        self.verify_operating_systems()?;
+        self.verify_environments()?;
         self.verify_injects()?;
+        self.verify_expected_responses()?;
         self.verify_events()?;
         self.verify_scripts()?;
         self.verify_stories()?;
@@ -1020,6 +1400,86 @@ mod tests {
         parse_sdl(sdl).unwrap();
     }
 
+    #[test]
+    fn node_feature_plan_is_ordered_by_dependencies() {
+        let sdl = r#"
+            name: test-scenario
+            description: some-description
+            nodes:
+                web-server:
+                    type: VM
+                    source: debian11
+                    resources:
+                        ram: 2 gib
+                        cpu: 2
+                    roles:
+                        admin: root
+                        web: www-data
+                    features:
+                        web-app: web
+                        web-config: admin
+                        nginx: admin
+                        database: admin
+            features:
+                web-app:
+                    type: Artifact
+                    source: web-app
+                    dependencies:
+                        - web-config
+                        - database
+                web-config:
+                    type: Configuration
+                    source: web-config
+                    dependencies:
+                        - nginx
+                nginx:
+                    type: Service
+                    source: nginx
+                database:
+                    type: Service
+                    source: postgres
+                    dependencies:
+                        - nginx
+        "#;
+        let scenario = parse_sdl(sdl).unwrap();
+        let feature_plan = scenario.node_feature_plan("web-server").unwrap();
+
+        insta::assert_yaml_snapshot!(feature_plan);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Feature \"nginx\" is a dependency of Feature \"web-config\" but it is not attached to VM \"web-server\""
+    )]
+    fn node_feature_plan_requires_attached_dependencies() {
+        let sdl = r#"
+            name: test-scenario
+            description: some-description
+            nodes:
+                web-server:
+                    type: VM
+                    source: debian11
+                    resources:
+                        ram: 2 gib
+                        cpu: 2
+                    roles:
+                        admin: root
+                    features:
+                        web-config: admin
+            features:
+                web-config:
+                    type: Configuration
+                    source: web-config
+                    dependencies:
+                        - nginx
+                nginx:
+                    type: Service
+                    source: nginx
+        "#;
+        let scenario = parse_sdl(sdl).unwrap();
+        scenario.node_feature_plan("web-server").unwrap();
+    }
+
     #[test]
     fn parent_features_dependencies_are_built_correctly() {
         let sdl = r#"
@@ -13,6 +13,96 @@ pub enum MetricType {
     Conditional,
 }
 
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub enum ArtifactType {
+    #[serde(alias = "file", alias = "FILE")]
+    File,
+    #[serde(alias = "report", alias = "REPORT")]
+    Report,
+    #[serde(alias = "screenshot", alias = "SCREENSHOT")]
+    Screenshot,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Criterion {
+    #[serde(alias = "Description", alias = "DESCRIPTION")]
+    pub description: Option<String>,
+    #[serde(
+        default,
+        rename = "min-points",
+        alias = "Min-points",
+        alias = "MIN-POINTS"
+    )]
+    pub min_points: u32,
+    #[serde(rename = "max-points", alias = "Max-points", alias = "MAX-POINTS")]
+    pub max_points: u32,
+    #[serde(alias = "Instructions", alias = "INSTRUCTIONS")]
+    pub instructions: Option<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Rubric {
+    #[serde(alias = "Instructions", alias = "INSTRUCTIONS")]
+    pub instructions: Option<String>,
+    #[serde(
+        default,
+        rename = "artifact-type",
+        alias = "Artifact-type",
+        alias = "ARTIFACT-TYPE"
+    )]
+    pub artifact_type: Option<ArtifactType>,
+    #[serde(
+        default,
+        rename = "artifact-format",
+        alias = "Artifact-format",
+        alias = "ARTIFACT-FORMAT"
+    )]
+    pub artifact_format: Option<String>,
+    #[serde(alias = "Criteria", alias = "CRITERIA")]
+    pub criteria: HashMap<String, Criterion>,
+}
+
+impl Rubric {
+    pub fn max_points(&self) -> Result<u32> {
+        self.criteria
+            .values()
+            .try_fold(0u32, |max_points, criterion| {
+                max_points.checked_add(criterion.max_points).ok_or_else(|| {
+                    anyhow!(
+                        "Sum of Rubric criteria `max-points` can not exceed {}",
+                        u32::MAX
+                    )
+                })
+            })
+    }
+}
+
+impl Formalize for Rubric {
+    fn formalize(&mut self) -> Result<()> {
+        if self.criteria.is_empty() {
+            return Err(anyhow!("Metric Rubric must have at least one Criterion"));
+        }
+        for (criterion_name, criterion) in self.criteria.iter() {
+            if criterion.max_points == 0 {
+                return Err(anyhow!(
+                    "Rubric Criterion \"{criterion_name}\" `max-points` can not be 0"
+                ));
+            }
+            if criterion.min_points > criterion.max_points {
+                return Err(anyhow!(
+                    "Rubric Criterion \"{criterion_name}\" `min-points` can not be greater than `max-points`"
+                ));
+            }
+        }
+        if self.artifact_format.is_some() && self.artifact_type.is_none() {
+            return Err(anyhow!(
+                "Metric Rubric has `artifact-format` defined but is missing `artifact-type`"
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
 pub struct Metric {
     #[serde(default, alias = "Name", alias = "NAME")]
@@ -27,6 +117,8 @@ pub struct Metric {
     pub condition: Option<String>,
     #[serde(alias = "Description", alias = "DESCRIPTION")]
     pub description: Option<String>,
+    #[serde(default, alias = "Rubric", alias = "RUBRIC")]
+    pub rubric: Option<Rubric>,
 }
 
 pub type Metrics = HashMap<String, Metric>;
@@ -41,6 +133,19 @@ impl Formalize for Metric {
                 if self.condition.is_some() {
                     return Err(anyhow!("Manual Metric can not have a Condition"));
                 }
+                if let Some(rubric) = &mut self.rubric {
+                    rubric.formalize()?;
+                    if rubric.max_points()? != self.max_score {
+                        return Err(anyhow!(
+                            "Metric Rubric criteria `max-points` must add up to `max-score`"
+                        ));
+                    }
+                    if rubric.artifact_type.is_some() && self.artifact == Some(false) {
+                        return Err(anyhow!(
+                            "Metric Rubric expects an Artifact but the Metric has Artifact disabled"
+                        ));
+                    }
+                }
             }
             MetricType::Conditional => {
                 if self.condition.is_none() {
@@ -49,6 +154,9 @@ impl Formalize for Metric {
                 if self.artifact.is_some() {
                     return Err(anyhow!("Conditional Metric can not have an Artifact"));
                 }
+                if self.rubric.is_some() {
+                    return Err(anyhow!("Conditional Metric can not have a Rubric"));
+                }
             }
         }
         Ok(())
@@ -159,4 +267,80 @@ mod tests {
         let mut metric: Metric = serde_yaml::from_str(metric_string).unwrap();
         assert!(metric.formalize().is_err());
     }
+
+    #[test]
+    fn parses_manual_metric_with_rubric() {
+        let sdl = r#"
+            name: test-scenario
+            metrics:
+                incident-report:
+                    type: MANUAL
+                    artifact: true
+                    max-score: 20
+                    rubric:
+                        instructions: Grade the report submitted after the phishing wave
+                        artifact-type: report
+                        artifact-format: pdf
+                        criteria:
+                            timeline:
+                                description: Attack timeline is reconstructed
+                                max-points: 10
+                            mitigation:
+                                description: Mitigations are proposed
+                                min-points: 2
+                                max-points: 10
+                                instructions: Give 2 points for any attempt
+        "#;
+        let metrics = parse_sdl(sdl).unwrap().metrics;
+        insta::with_settings!({sort_maps => true}, {
+                insta::assert_yaml_snapshot!(metrics);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Metric Rubric criteria `max-points` must add up to `max-score`")]
+    fn fails_rubric_not_matching_max_score() {
+        let metric_string = r#"
+          type: MANUAL
+          max-score: 10
+          rubric:
+            criteria:
+              timeline:
+                max-points: 5
+        "#;
+        let mut metric: Metric = serde_yaml::from_str(metric_string).unwrap();
+        metric.formalize().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Sum of Rubric criteria `max-points` can not exceed 4294967295")]
+    fn fails_rubric_with_overflowing_max_points() {
+        let metric_string = r#"
+          type: MANUAL
+          max-score: 10
+          rubric:
+            criteria:
+              timeline:
+                max-points: 4294967295
+              report:
+                max-points: 1
+        "#;
+        let mut metric: Metric = serde_yaml::from_str(metric_string).unwrap();
+        metric.formalize().unwrap();
+    }
+
+    #[test]
+    fn fails_conditional_metric_with_rubric() {
+        let metric_string = r#"
+          type: CONDITIONAL
+          max-score: 10
+          condition: some-condition
+          rubric:
+            criteria:
+              timeline:
+                max-points: 10
+        "#;
+        let mut metric: Metric = serde_yaml::from_str(metric_string).unwrap();
+        assert!(metric.formalize().is_err());
+    }
 }
@@ -1,6 +1,15 @@
 use crate::{
-    condition::Condition, entity::Entity, feature::Feature, helpers::Connection,
-    infrastructure::Infrastructure, inject::Inject, vulnerability::Vulnerability, Formalize,
+    condition::Condition,
+    constants::SSH_PUBLIC_KEY_REGEX,
+    credential::{Password, PrivilegeLevel},
+    entity::Entity,
+    feature::Feature,
+    helpers::Connection,
+    infrastructure::Infrastructure,
+    inject::Inject,
+    operating_system::OperatingSystem,
+    vulnerability::Vulnerability,
+    Formalize,
 };
 use anyhow::{anyhow, Result};
 use bytesize::ByteSize;
@@ -10,7 +19,7 @@ use std::collections::HashMap;
 
 use crate::common::{HelperSource, Source};
 
-fn parse_bytesize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+pub(crate) fn parse_bytesize<'de, D>(deserializer: D) -> Result<u64, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -52,6 +61,8 @@ pub struct VM {
         deserialize_with = "deserialize_struct_case_insensitive"
     )]
     pub resources: Resources,
+    #[serde(default, alias = "Os", alias = "OS")]
+    pub os: Option<OperatingSystem>,
     #[serde(default, alias = "Features", alias = "FEATURES")]
     pub features: HashMap<String, String>,
     #[serde(default, alias = "Conditions", alias = "CONDITIONS")]
@@ -85,10 +96,53 @@ pub struct Role {
     pub username: String,
     #[serde(alias = "Entity", alias = "ENTITY")]
     pub entities: Option<Vec<String>>,
+    #[serde(default, alias = "Password", alias = "PASSWORD")]
+    pub password: Option<Password>,
+    #[serde(default, rename = "ssh-keys", alias = "Ssh-keys", alias = "SSH-KEYS")]
+    pub ssh_keys: Option<Vec<String>>,
+    #[serde(default, alias = "Privilege", alias = "PRIVILEGE")]
+    pub privilege: Option<PrivilegeLevel>,
 }
 
 pub type Roles = HashMap<String, Role>;
 
+impl Formalize for Role {
+    fn formalize(&mut self) -> Result<()> {
+        match &mut self.password {
+            Some(Password::Policy(policy)) => policy.formalize()?,
+            Some(Password::Reference(reference)) if reference.is_empty() => {
+                return Err(anyhow!(
+                    "Role \"{username}\" password reference can not be empty",
+                    username = self.username
+                ));
+            }
+            _ => {}
+        }
+        if let Some(ssh_keys) = &self.ssh_keys {
+            for ssh_key in ssh_keys {
+                if !SSH_PUBLIC_KEY_REGEX.is_match(ssh_key) {
+                    return Err(anyhow!(
+                        "Role \"{username}\" has an invalid SSH public key",
+                        username = self.username
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct RoleUsage {
+    pub username: String,
+    pub entities: Option<Vec<String>>,
+    pub features: Vec<String>,
+    pub conditions: Vec<String>,
+    pub injects: Vec<String>,
+}
+
+pub type RoleReport = HashMap<String, HashMap<String, RoleUsage>>;
+
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
 pub struct Node {
     #[serde(flatten)]
@@ -120,6 +174,9 @@ impl From<HelperRoles> for Roles {
                         RoleTypes::Username(username) => Role {
                             username,
                             entities: None,
+                            password: None,
+                            ssh_keys: None,
+                            privilege: None,
                         },
                     };
                     (role_name, role_value)
@@ -304,6 +361,18 @@ impl Connection<Entity> for (&String, &Option<HashMap<String, Role>>) {
     }
 }
 
+impl VM {
+    pub fn names_running_as(role_map: &HashMap<String, String>, role_name: &str) -> Vec<String> {
+        let mut names = role_map
+            .iter()
+            .filter(|(_, role)| role.as_str() == role_name)
+            .map(|(name, _)| name.to_owned())
+            .collect::<Vec<String>>();
+        names.sort();
+        names
+    }
+}
+
 impl Formalize for VM {
     fn formalize(&mut self) -> Result<()> {
         if let Some(source_helper) = &self._source_helper {
@@ -313,7 +382,9 @@ impl Formalize for VM {
         }
 
         if let Some(helper_roles) = &self._roles_helper {
-            self.roles = Some(helper_roles.to_owned().into());
+            let mut roles: Roles = helper_roles.to_owned().into();
+            roles.values_mut().try_for_each(|role| role.formalize())?;
+            self.roles = Some(roles);
         }
 
         Ok(())
@@ -550,6 +621,82 @@ mod tests {
         parse_sdl(sdl).unwrap();
     }
 
+    #[test]
+    #[should_panic(expected = "Role admni not found under for Node win-10's roles")]
+    fn role_under_inject_missing_from_node() {
+        let sdl = r#"
+            name: test-scenario
+            description: some-description
+            nodes:
+                win-10:
+                    type: VM
+                    resources:
+                        ram: 2 gib
+                        cpu: 2
+                    source: windows10
+                    roles:
+                        admin: "username"
+                    injects:
+                        inject-1: "admni"
+            injects:
+                inject-1:
+                    source: dl-library
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    fn role_report_lists_usage_per_role() {
+        let sdl = r#"
+            name: test-scenario
+            description: some-description
+            nodes:
+                win-10:
+                    type: VM
+                    resources:
+                        ram: 2 gib
+                        cpu: 2
+                    source: windows10
+                    roles:
+                        admin:
+                            username: "Administrator"
+                            entities:
+                                - blue-team
+                        user: "user"
+                        auditor: "auditor"
+                    features:
+                        feature-1: admin
+                        feature-2: user
+                    conditions:
+                        condition-1: admin
+                    injects:
+                        inject-1: user
+                switch-1:
+                    type: Switch
+            features:
+                feature-1:
+                    type: service
+                    source: dl-library
+                feature-2:
+                    type: configuration
+                    source: dl-library
+            conditions:
+                condition-1:
+                    command: executable/path.sh
+                    interval: 30
+            injects:
+                inject-1:
+                    source: dl-library
+            entities:
+                blue-team:
+                    name: The Blue Team
+        "#;
+        let role_report = parse_sdl(sdl).unwrap().role_report();
+        insta::with_settings!({sort_maps => true}, {
+                insta::assert_yaml_snapshot!(role_report);
+        });
+    }
+
     #[test]
     fn same_name_for_role_only_saves_one_role() {
         let sdl = r#"
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+
+use crate::constants::WINDOWS_PATH_REGEX;
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub enum OsFamily {
+    #[serde(alias = "windows", alias = "WINDOWS")]
+    Windows,
+    #[serde(alias = "linux", alias = "LINUX")]
+    Linux,
+    #[serde(alias = "macos", alias = "MACOS", alias = "MacOs")]
+    MacOS,
+    #[serde(alias = "freebsd", alias = "FREEBSD", alias = "FreeBsd")]
+    FreeBSD,
+    #[serde(alias = "other", alias = "OTHER")]
+    Other,
+}
+
+impl OsFamily {
+    pub fn accepts_path(&self, path: &str) -> bool {
+        let is_windows_path = WINDOWS_PATH_REGEX.is_match(path);
+        match self {
+            OsFamily::Windows => !path.starts_with('/'),
+            OsFamily::Other => true,
+            _ => !is_windows_path,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub enum Architecture {
+    #[serde(
+        alias = "amd64",
+        alias = "AMD64",
+        alias = "x86_64",
+        alias = "X86_64",
+        alias = "x86-64"
+    )]
+    Amd64,
+    #[serde(alias = "arm64", alias = "ARM64", alias = "aarch64", alias = "AARCH64")]
+    Arm64,
+    #[serde(alias = "x86", alias = "i386", alias = "i686")]
+    X86,
+    #[serde(alias = "arm", alias = "ARM", alias = "armv7")]
+    Arm,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct OperatingSystem {
+    #[serde(alias = "Family", alias = "FAMILY")]
+    pub family: OsFamily,
+    #[serde(default, alias = "Distribution", alias = "DISTRIBUTION")]
+    pub distribution: Option<String>,
+    #[serde(default, alias = "Version", alias = "VERSION")]
+    pub version: Option<String>,
+    #[serde(default, alias = "Architecture", alias = "ARCHITECTURE")]
+    pub architecture: Option<Architecture>,
+}
+
+impl OperatingSystem {
+    pub fn is_supported_by(&self, potential_supported_os: &Option<Vec<OsFamily>>) -> bool {
+        match potential_supported_os {
+            Some(supported_os) => supported_os.contains(&self.family),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sdl;
+
+    #[test]
+    fn parses_sdl_with_operating_systems() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                win-10:
+                    type: VM
+                    source: windows10
+                    resources:
+                        ram: 4 gib
+                        cpu: 2
+                    os:
+                        family: windows
+                        version: "10"
+                        architecture: x86_64
+                    roles:
+                        admin: "Administrator"
+                    features:
+                        windows-service: admin
+            features:
+                windows-service:
+                    type: service
+                    source: windows-service
+                    destination: C:\Program Files\service
+                    supported-os:
+                        - windows
+        "#;
+        let scenario = parse_sdl(sdl).unwrap();
+        insta::with_settings!({sort_maps => true}, {
+                insta::assert_yaml_snapshot!(scenario.nodes);
+        });
+    }
+
+    #[test]
+    fn path_styles_are_recognized() {
+        assert!(OsFamily::Windows.accepts_path(r"C:\Program Files\service"));
+        assert!(!OsFamily::Windows.accepts_path("/opt/service"));
+        assert!(OsFamily::Linux.accepts_path("/opt/service"));
+        assert!(!OsFamily::Linux.accepts_path(r"C:\Program Files\service"));
+        assert!(!OsFamily::Linux.accepts_path(r"\\fileserver\share"));
+        assert!(OsFamily::Other.accepts_path(r"C:\Program Files\service"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Feature \"windows-service\" does not support the Operating System family Linux of VM \"debian\""
+    )]
+    fn windows_feature_on_linux_vm_is_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                debian:
+                    type: VM
+                    source: debian11
+                    resources:
+                        ram: 2 gib
+                        cpu: 1
+                    os:
+                        family: linux
+                        distribution: debian
+                    roles:
+                        admin: "root"
+                    features:
+                        windows-service: admin
+            features:
+                windows-service:
+                    type: service
+                    source: windows-service
+                    supported-os:
+                        - windows
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = r#"Feature "windows-service" Destination "C:\Program Files\service" is not a valid path for the Operating System family Linux of VM "debian""#
+    )]
+    fn windows_destination_on_linux_vm_is_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                debian:
+                    type: VM
+                    source: debian11
+                    resources:
+                        ram: 2 gib
+                        cpu: 1
+                    os:
+                        family: linux
+                    roles:
+                        admin: "root"
+                    features:
+                        windows-service: admin
+            features:
+                windows-service:
+                    type: service
+                    source: windows-service
+                    destination: C:\Program Files\service
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Condition \"event-log-check\" does not support the Operating System family Linux of VM \"debian\""
+    )]
+    fn windows_condition_on_linux_vm_is_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                debian:
+                    type: VM
+                    source: debian11
+                    resources:
+                        ram: 2 gib
+                        cpu: 1
+                    os:
+                        family: linux
+                    roles:
+                        admin: "root"
+                    conditions:
+                        event-log-check: admin
+            conditions:
+                event-log-check:
+                    source: event-log-checker
+                    supported-os:
+                        - Windows
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+
+    #[test]
+    fn vm_without_operating_system_is_not_checked() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                debian:
+                    type: VM
+                    source: debian11
+                    resources:
+                        ram: 2 gib
+                        cpu: 1
+                    roles:
+                        admin: "root"
+                    injects:
+                        windows-inject: admin
+            injects:
+                windows-inject:
+                    source: windows-inject
+                    supported-os:
+                        - windows
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+}
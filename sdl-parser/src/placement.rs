@@ -0,0 +1,505 @@
+use anyhow::{anyhow, Result};
+use bytesize::ByteSize;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::{
+    infrastructure::Infrastructure,
+    node::{parse_bytesize, NodeType, Resources},
+    Scenario,
+};
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Host {
+    #[serde(alias = "Name", alias = "NAME")]
+    pub name: String,
+    #[serde(alias = "Cpu", alias = "CPU")]
+    pub cpu: u32,
+    #[serde(deserialize_with = "parse_bytesize", alias = "Ram", alias = "RAM")]
+    pub ram: u64,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Clone, Ord, PartialOrd)]
+pub struct NodeInstance {
+    pub node_name: String,
+    pub index: u32,
+}
+
+impl fmt::Display for NodeInstance {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}-{}", self.node_name, self.index)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Placement {
+    pub hosts: HashMap<String, Vec<NodeInstance>>,
+}
+
+impl Placement {
+    pub fn host_of(&self, node_name: &str, index: u32) -> Option<&String> {
+        self.hosts.iter().find_map(|(host_name, instances)| {
+            instances
+                .iter()
+                .any(|instance| instance.node_name == node_name && instance.index == index)
+                .then_some(host_name)
+        })
+    }
+}
+
+struct PlacementUnit {
+    instances: Vec<NodeInstance>,
+    cpu: u32,
+    ram: u64,
+}
+
+struct HostCapacity<'a> {
+    host: &'a Host,
+    free_cpu: u32,
+    free_ram: u64,
+    node_names: HashSet<String>,
+}
+
+fn find_affinity_group(groups: &mut HashMap<String, String>, node_name: &str) -> String {
+    let parent = groups[node_name].clone();
+    if parent == node_name {
+        return parent;
+    }
+    let root = find_affinity_group(groups, &parent);
+    groups.insert(node_name.to_owned(), root.clone());
+    root
+}
+
+fn build_anti_affinities(infrastructure: &Infrastructure) -> HashSet<(String, String)> {
+    let mut anti_affinities = HashSet::new();
+    for (node_name, infra_node) in infrastructure.iter() {
+        if let Some(anti_affinity) = &infra_node.anti_affinity {
+            for other_node_name in anti_affinity {
+                anti_affinities.insert((node_name.to_owned(), other_node_name.to_owned()));
+                anti_affinities.insert((other_node_name.to_owned(), node_name.to_owned()));
+            }
+        }
+    }
+    anti_affinities
+}
+
+fn build_placement_units(
+    infrastructure: &Infrastructure,
+    vm_resources: &HashMap<String, Resources>,
+    anti_affinities: &HashSet<(String, String)>,
+) -> Result<Vec<PlacementUnit>> {
+    let mut groups: HashMap<String, String> = vm_resources
+        .keys()
+        .map(|node_name| (node_name.to_owned(), node_name.to_owned()))
+        .collect();
+
+    for (node_name, infra_node) in infrastructure.iter() {
+        if !vm_resources.contains_key(node_name) {
+            continue;
+        }
+        if let Some(affinity) = &infra_node.affinity {
+            for other_node_name in affinity {
+                if !vm_resources.contains_key(other_node_name) {
+                    continue;
+                }
+                let root = find_affinity_group(&mut groups, node_name);
+                let other_root = find_affinity_group(&mut groups, other_node_name);
+                if root != other_root {
+                    groups.insert(other_root, root);
+                }
+            }
+        }
+    }
+
+    let mut grouped_node_names: HashMap<String, Vec<String>> = HashMap::new();
+    let mut node_names = vm_resources.keys().cloned().collect::<Vec<String>>();
+    node_names.sort();
+    for node_name in node_names {
+        let root = find_affinity_group(&mut groups, &node_name);
+        grouped_node_names.entry(root).or_default().push(node_name);
+    }
+
+    let mut units = vec![];
+    for node_names in grouped_node_names.into_values() {
+        let mut unit = PlacementUnit {
+            instances: vec![],
+            cpu: 0,
+            ram: 0,
+        };
+        for node_name in node_names.iter() {
+            let is_self_affine = infrastructure[node_name]
+                .affinity
+                .as_ref()
+                .is_some_and(|affinity| affinity.contains(node_name));
+            let count = infrastructure[node_name].count as u32;
+            if node_names.len() == 1 && !is_self_affine {
+                for index in 0..count {
+                    units.push(PlacementUnit {
+                        instances: vec![NodeInstance {
+                            node_name: node_name.to_owned(),
+                            index,
+                        }],
+                        cpu: vm_resources[node_name].cpu,
+                        ram: vm_resources[node_name].ram,
+                    });
+                }
+                continue;
+            }
+
+            for other_node_name in node_names.iter() {
+                let is_conflicting =
+                    anti_affinities.contains(&(node_name.to_owned(), other_node_name.to_owned()));
+                let is_same_multi_instance_node = node_name == other_node_name && count > 1;
+                if is_conflicting && (node_name != other_node_name || is_same_multi_instance_node) {
+                    return Err(anyhow!(
+                        "Node \"{node_name}\" is required to share a Host with \"{other_node_name}\" by Affinity but they also have Anti-affinity"
+                    ));
+                }
+            }
+
+            for index in 0..count {
+                unit.instances.push(NodeInstance {
+                    node_name: node_name.to_owned(),
+                    index,
+                });
+                unit.cpu += vm_resources[node_name].cpu;
+                unit.ram += vm_resources[node_name].ram;
+            }
+        }
+        if !unit.instances.is_empty() {
+            units.push(unit);
+        }
+    }
+
+    units.sort_by(|unit, other_unit| {
+        other_unit
+            .cpu
+            .cmp(&unit.cpu)
+            .then(other_unit.ram.cmp(&unit.ram))
+            .then(unit.instances.cmp(&other_unit.instances))
+    });
+
+    Ok(units)
+}
+
+fn describe_unit(unit: &PlacementUnit) -> String {
+    let instance_names = unit
+        .instances
+        .iter()
+        .map(|instance| format!("\"{instance}\""))
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!(
+        "{instance_names} (cpu: {cpu}, ram: {ram})",
+        cpu = unit.cpu,
+        ram = ByteSize(unit.ram)
+    )
+}
+
+impl Scenario {
+    pub fn place_nodes(&self, hosts: &[Host]) -> Result<Placement> {
+        let mut host_names = HashSet::new();
+        for host in hosts {
+            if !host_names.insert(&host.name) {
+                return Err(anyhow!("Duplicate Host name \"{}\"", host.name));
+            }
+        }
+
+        let mut placement = Placement {
+            hosts: hosts
+                .iter()
+                .map(|host| (host.name.to_owned(), vec![]))
+                .collect(),
+        };
+        let (Some(infrastructure), Some(nodes)) = (&self.infrastructure, &self.nodes) else {
+            return Ok(placement);
+        };
+
+        let vm_resources = infrastructure
+            .keys()
+            .filter_map(|node_name| match nodes.get(node_name) {
+                Some(node) => match &node.type_field {
+                    NodeType::VM(vm) => Some((node_name.to_owned(), vm.resources.clone())),
+                    _ => None,
+                },
+                None => None,
+            })
+            .collect::<HashMap<String, Resources>>();
+        let anti_affinities = build_anti_affinities(infrastructure);
+        let units = build_placement_units(infrastructure, &vm_resources, &anti_affinities)?;
+
+        let mut capacities = hosts
+            .iter()
+            .map(|host| HostCapacity {
+                host,
+                free_cpu: host.cpu,
+                free_ram: host.ram,
+                node_names: HashSet::new(),
+            })
+            .collect::<Vec<HostCapacity>>();
+
+        for unit in units {
+            let has_capacity = |capacity: &HostCapacity| {
+                capacity.free_cpu >= unit.cpu && capacity.free_ram >= unit.ram
+            };
+            let has_anti_affinity = |capacity: &HostCapacity| {
+                unit.instances.iter().any(|instance| {
+                    capacity.node_names.iter().any(|node_name| {
+                        anti_affinities
+                            .contains(&(instance.node_name.to_owned(), node_name.to_owned()))
+                    })
+                })
+            };
+
+            let Some(capacity) = capacities
+                .iter_mut()
+                .find(|capacity| has_capacity(capacity) && !has_anti_affinity(capacity))
+            else {
+                if hosts
+                    .iter()
+                    .all(|host| host.cpu < unit.cpu || host.ram < unit.ram)
+                {
+                    return Err(anyhow!(
+                        "Node instances {} do not fit on any Host",
+                        describe_unit(&unit)
+                    ));
+                } else if capacities.iter().any(has_capacity) {
+                    return Err(anyhow!(
+                        "Node instances {} can not be placed without breaking Anti-affinity",
+                        describe_unit(&unit)
+                    ));
+                }
+                return Err(anyhow!(
+                    "Node instances {} can not be placed, Hosts are out of capacity",
+                    describe_unit(&unit)
+                ));
+            };
+
+            capacity.free_cpu -= unit.cpu;
+            capacity.free_ram -= unit.ram;
+            for instance in unit.instances {
+                capacity.node_names.insert(instance.node_name.to_owned());
+                if let Some(host_instances) = placement.hosts.get_mut(&capacity.host.name) {
+                    host_instances.push(instance);
+                }
+            }
+        }
+
+        placement
+            .hosts
+            .values_mut()
+            .for_each(|instances| instances.sort());
+
+        Ok(placement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        nodes:
+            switch-1:
+                type: Switch
+            web:
+                type: VM
+                source: web-server
+                resources:
+                    cpu: 2
+                    ram: 4 gib
+            database:
+                type: VM
+                source: database-server
+                resources:
+                    cpu: 4
+                    ram: 8 gib
+            workstation:
+                type: VM
+                source: windows10
+                resources:
+                    cpu: 1
+                    ram: 2 gib
+        infrastructure:
+            switch-1: 1
+            web:
+                count: 2
+                links:
+                    - switch-1
+                anti-affinity:
+                    - web
+            database:
+                links:
+                    - switch-1
+                affinity:
+                    - workstation
+            workstation: 3
+    "#;
+
+    fn hosts(sdl: &str) -> Vec<Host> {
+        serde_yaml::from_str::<Vec<Host>>(sdl).unwrap()
+    }
+
+    #[test]
+    fn nodes_are_placed_on_hosts() {
+        let scenario = parse_sdl(SDL).unwrap();
+        let placement = scenario
+            .place_nodes(&hosts(
+                r#"
+                - name: host-1
+                  cpu: 12
+                  ram: 24 gib
+                - name: host-2
+                  cpu: 12
+                  ram: 24 gib
+                "#,
+            ))
+            .unwrap();
+
+        insta::with_settings!({sort_maps => true}, {
+                insta::assert_yaml_snapshot!(placement);
+        });
+    }
+
+    #[test]
+    fn placement_is_deterministic() {
+        let scenario = parse_sdl(SDL).unwrap();
+        let hosts = hosts(
+            r#"
+            - name: host-1
+              cpu: 16
+              ram: 32 gib
+            - name: host-2
+              cpu: 16
+              ram: 32 gib
+            "#,
+        );
+        let placement = scenario.place_nodes(&hosts).unwrap();
+
+        for _ in 0..10 {
+            assert_eq!(scenario.place_nodes(&hosts).unwrap(), placement);
+        }
+        assert_ne!(placement.host_of("web", 0), placement.host_of("web", 1));
+        assert_eq!(
+            placement.host_of("database", 0),
+            placement.host_of("workstation", 2)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "can not be placed without breaking Anti-affinity")]
+    fn anti_affinity_is_respected() {
+        let scenario = parse_sdl(SDL).unwrap();
+        scenario
+            .place_nodes(&hosts(
+                r#"
+                - name: host-1
+                  cpu: 32
+                  ram: 64 gib
+                "#,
+            ))
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Node instances \"database-0\", \"web-0\", \"web-1\" (cpu: 8")]
+    fn too_small_hosts_are_reported() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                web:
+                    type: VM
+                    source: web-server
+                    resources:
+                        cpu: 2
+                        ram: 4 gib
+                database:
+                    type: VM
+                    source: database-server
+                    resources:
+                        cpu: 4
+                        ram: 8 gib
+            infrastructure:
+                web: 2
+                database:
+                    affinity:
+                        - web
+        "#;
+        let scenario = parse_sdl(sdl).unwrap();
+        scenario
+            .place_nodes(&hosts(
+                r#"
+                - name: host-1
+                  cpu: 6
+                  ram: 64 gib
+                "#,
+            ))
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Node \"web\" is required to share a Host with \"web\" by Affinity but they also have Anti-affinity"
+    )]
+    fn conflicting_affinities_are_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                web:
+                    type: VM
+                    source: web-server
+                    resources:
+                        cpu: 2
+                        ram: 4 gib
+                database:
+                    type: VM
+                    source: database-server
+                    resources:
+                        cpu: 4
+                        ram: 8 gib
+            infrastructure:
+                web:
+                    count: 2
+                    anti-affinity:
+                        - web
+                database:
+                    affinity:
+                        - web
+        "#;
+        let scenario = parse_sdl(sdl).unwrap();
+        scenario
+            .place_nodes(&hosts(
+                r#"
+                - name: host-1
+                  cpu: 32
+                  ram: 64 gib
+                "#,
+            ))
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Infrastructure entry \"mail\" does not exist under Infrastructure even though it is an affinity hint for \"web\""
+    )]
+    fn missing_affinity_node_is_rejected() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                web:
+                    type: VM
+                    source: web-server
+                    resources:
+                        cpu: 2
+                        ram: 4 gib
+            infrastructure:
+                web:
+                    affinity:
+                        - mail
+        "#;
+        parse_sdl(sdl).unwrap();
+    }
+}
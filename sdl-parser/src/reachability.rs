@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    condition::{Condition, ConditionEvaluation},
+    node::NodeType,
+    reference::{ElementId, ElementKind},
+    Scenario,
+};
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct ConditionBlockedElement {
+    pub element: ElementId,
+    pub undefined_conditions: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Reachability {
+    pub reachable: BTreeSet<ElementId>,
+    pub unreachable: BTreeSet<ElementId>,
+    pub condition_blocked: Vec<ConditionBlockedElement>,
+}
+
+impl Reachability {
+    pub fn is_reachable(&self, kind: ElementKind, name: &str) -> bool {
+        self.reachable.contains(&ElementId::new(kind, name))
+    }
+}
+
+impl Scenario {
+    pub fn reachability(&self) -> Reachability {
+        let mut reachable = BTreeSet::new();
+        let mut root_events = vec![];
+        for (story_name, story) in self.stories.iter().flatten() {
+            reachable.insert(ElementId::new(ElementKind::Story, story_name));
+            for script_name in story.scripts.iter() {
+                let Some(script) = self
+                    .scripts
+                    .as_ref()
+                    .and_then(|scripts| scripts.get(script_name))
+                else {
+                    continue;
+                };
+                reachable.insert(ElementId::new(ElementKind::Script, script_name));
+                root_events.extend(script.events.keys().cloned());
+            }
+        }
+        root_events.sort();
+        root_events.dedup();
+        for metric in self.metrics.iter().flat_map(|metrics| metrics.values()) {
+            if let Some(condition_name) = &metric.condition {
+                self.reach_condition(condition_name, &mut reachable);
+            }
+        }
+
+        let mut effectively_reachable = reachable.to_owned();
+        let mut blocked_events = BTreeMap::new();
+        self.walk_events(&root_events, false, &mut reachable, &mut BTreeMap::new());
+        self.walk_events(
+            &root_events,
+            true,
+            &mut effectively_reachable,
+            &mut blocked_events,
+        );
+
+        let mut undefined_conditions = BTreeMap::<ElementId, BTreeSet<String>>::new();
+        for (event_name, conditions) in blocked_events {
+            let mut blocked = BTreeSet::new();
+            self.walk_events(&[event_name], false, &mut blocked, &mut BTreeMap::new());
+            for element in blocked.difference(&effectively_reachable) {
+                undefined_conditions
+                    .entry(element.to_owned())
+                    .or_default()
+                    .extend(conditions.iter().cloned());
+            }
+        }
+
+        let mut unreachable = BTreeSet::new();
+        let defined_elements = [
+            (
+                ElementKind::Script,
+                self.scripts
+                    .iter()
+                    .flat_map(|scripts| scripts.keys())
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                ElementKind::Event,
+                self.events
+                    .iter()
+                    .flat_map(|events| events.keys())
+                    .collect(),
+            ),
+            (
+                ElementKind::Inject,
+                self.injects
+                    .iter()
+                    .flat_map(|injects| injects.keys())
+                    .collect(),
+            ),
+            (
+                ElementKind::Condition,
+                self.conditions
+                    .iter()
+                    .flat_map(|conditions| conditions.keys())
+                    .collect(),
+            ),
+        ];
+        for (kind, names) in defined_elements {
+            for name in names {
+                let element = ElementId::new(kind, name);
+                if !reachable.contains(&element) {
+                    unreachable.insert(element);
+                }
+            }
+        }
+
+        Reachability {
+            reachable,
+            unreachable,
+            condition_blocked: undefined_conditions
+                .into_iter()
+                .map(|(element, conditions)| ConditionBlockedElement {
+                    element,
+                    undefined_conditions: conditions.into_iter().collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn walk_events(
+        &self,
+        event_names: &[String],
+        respect_conditions: bool,
+        reached: &mut BTreeSet<ElementId>,
+        blocked_events: &mut BTreeMap<String, Vec<String>>,
+    ) {
+        let mut stack = event_names.to_vec();
+        let mut visited = BTreeSet::new();
+        while let Some(event_name) = stack.pop() {
+            if !visited.insert(event_name.to_owned()) {
+                continue;
+            }
+            let Some(event) = self
+                .events
+                .as_ref()
+                .and_then(|events| events.get(&event_name))
+            else {
+                continue;
+            };
+
+            for condition_name in event.conditions.iter().flatten() {
+                self.reach_condition(condition_name, reached);
+            }
+            if respect_conditions
+                && !event
+                    .are_conditions_met(|condition_name| self.is_condition_defined(condition_name))
+            {
+                let mut undefined_conditions = event
+                    .conditions
+                    .iter()
+                    .flatten()
+                    .filter(|condition_name| !self.is_condition_defined(condition_name))
+                    .cloned()
+                    .collect::<Vec<String>>();
+                undefined_conditions.sort();
+                blocked_events.insert(event_name, undefined_conditions);
+                continue;
+            }
+
+            reached.insert(ElementId::new(ElementKind::Event, &event_name));
+            for inject_name in event.injects.iter().flatten() {
+                reached.insert(ElementId::new(ElementKind::Inject, inject_name));
+                let responses = self
+                    .injects
+                    .as_ref()
+                    .and_then(|injects| injects.get(inject_name))
+                    .and_then(|inject| inject.expected_responses.as_ref());
+                for response in responses
+                    .into_iter()
+                    .flat_map(|responses| responses.values())
+                {
+                    if let Some(condition_name) = &response.condition {
+                        self.reach_condition(condition_name, reached);
+                    }
+                }
+            }
+            for (follower_name, follower) in self.events.iter().flatten() {
+                if follower.triggered_after() == Some(&event_name) {
+                    stack.push(follower_name.to_owned());
+                }
+            }
+        }
+    }
+
+    fn reach_condition(&self, condition_name: &str, reached: &mut BTreeSet<ElementId>) {
+        for condition_name in Condition::transitive_conditions(condition_name, &self.conditions) {
+            reached.insert(ElementId::new(ElementKind::Condition, &condition_name));
+        }
+    }
+
+    fn is_condition_defined(&self, condition_name: &str) -> bool {
+        Condition::evaluate(
+            condition_name,
+            &self.conditions,
+            ConditionEvaluation::Availability,
+            &|condition_name| {
+                self.nodes
+                    .iter()
+                    .flatten()
+                    .any(|(_, node)| match &node.type_field {
+                        NodeType::VM(vm) => vm.conditions.contains_key(condition_name),
+                        _ => false,
+                    })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        nodes:
+            mail:
+                type: VM
+                source: mail-server
+                resources:
+                    ram: 2 gib
+                    cpu: 1
+                roles:
+                    admin: root
+                conditions:
+                    mail-up: admin
+        infrastructure:
+            mail: 1
+        conditions:
+            mail-up:
+                command: executable/path.sh
+                interval: 30
+            web-up:
+                command: executable/path.sh
+                interval: 30
+            orphan-check:
+                command: executable/path.sh
+                interval: 30
+            scored-check:
+                command: executable/path.sh
+                interval: 30
+        metrics:
+            metric-1:
+                type: CONDITIONAL
+                max-score: 10
+                condition: scored-check
+        injects:
+            phishing-email:
+                source: inject-package
+            defacement-notice:
+                source: inject-package
+            orphan-inject:
+                source: inject-package
+        events:
+            phishing-wave:
+                conditions:
+                    - mail-up
+                injects:
+                    - phishing-email
+            defacement:
+                conditions:
+                    - web-up
+                injects:
+                    - defacement-notice
+            defacement-follow-up:
+                trigger:
+                    type: after
+                    event: defacement
+            orphan-event:
+                injects:
+                    - orphan-inject
+        scripts:
+            main-script:
+                start-time: 0
+                end-time: 2h
+                speed: 1
+                events:
+                    phishing-wave: 10min
+                    defacement: 1h
+            orphan-script:
+                start-time: 0
+                end-time: 1h
+                speed: 1
+                events:
+                    orphan-event: 10min
+        stories:
+            main-story:
+                scripts:
+                    - main-script
+    "#;
+
+    #[test]
+    fn reports_unreachable_and_condition_blocked_elements() {
+        let reachability = parse_sdl(SDL).unwrap().reachability();
+        assert!(reachability.is_reachable(ElementKind::Inject, "phishing-email"));
+        assert!(reachability.is_reachable(ElementKind::Condition, "scored-check"));
+        insta::assert_yaml_snapshot!((reachability.unreachable, reachability.condition_blocked));
+    }
+}
@@ -0,0 +1,468 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::{entity::Flatten, node::NodeType, Scenario};
+
+#[derive(PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Clone, Copy, Ord, PartialOrd)]
+pub enum ElementKind {
+    Node,
+    Feature,
+    Condition,
+    Vulnerability,
+    Metric,
+    Evaluation,
+    Tlo,
+    Goal,
+    Entity,
+    Inject,
+    Event,
+    Script,
+    Story,
+}
+
+impl fmt::Display for ElementKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind_name = match self {
+            ElementKind::Node => "Node",
+            ElementKind::Feature => "Feature",
+            ElementKind::Condition => "Condition",
+            ElementKind::Vulnerability => "Vulnerability",
+            ElementKind::Metric => "Metric",
+            ElementKind::Evaluation => "Evaluation",
+            ElementKind::Tlo => "TLO",
+            ElementKind::Goal => "Goal",
+            ElementKind::Entity => "Entity",
+            ElementKind::Inject => "Inject",
+            ElementKind::Event => "Event",
+            ElementKind::Script => "Script",
+            ElementKind::Story => "Story",
+        };
+        write!(formatter, "{kind_name}")
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Clone, Ord, PartialOrd)]
+pub struct ElementId {
+    pub kind: ElementKind,
+    pub name: String,
+}
+
+impl ElementId {
+    pub fn new(kind: ElementKind, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ElementId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{} \"{}\"", self.kind, self.name)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ReferenceIndex {
+    elements: BTreeSet<ElementId>,
+    references: HashMap<ElementId, BTreeSet<ElementId>>,
+    referenced_by: HashMap<ElementId, BTreeSet<ElementId>>,
+}
+
+impl ReferenceIndex {
+    pub fn new(scenario: &Scenario) -> Self {
+        let mut index = Self::default();
+
+        if let Some(nodes) = &scenario.nodes {
+            for (node_name, node) in nodes.iter() {
+                let node_id = ElementId::new(ElementKind::Node, node_name);
+                index.add_element(node_id.to_owned());
+                if let NodeType::VM(vm) = &node.type_field {
+                    index.add_references(&node_id, ElementKind::Feature, vm.features.keys());
+                    index.add_references(&node_id, ElementKind::Condition, vm.conditions.keys());
+                    index.add_references(&node_id, ElementKind::Inject, vm.injects.keys());
+                    index.add_references(
+                        &node_id,
+                        ElementKind::Vulnerability,
+                        vm.vulnerabilities.iter(),
+                    );
+                    if let Some(roles) = &vm.roles {
+                        for role in roles.values() {
+                            index.add_references(
+                                &node_id,
+                                ElementKind::Entity,
+                                role.entities.iter().flatten(),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(infrastructure) = &scenario.infrastructure {
+            for (node_name, infra_node) in infrastructure.iter() {
+                let node_id = ElementId::new(ElementKind::Node, node_name);
+                for node_names in [
+                    &infra_node.links,
+                    &infra_node.dependencies,
+                    &infra_node.affinity,
+                    &infra_node.anti_affinity,
+                ] {
+                    index.add_references(&node_id, ElementKind::Node, node_names.iter().flatten());
+                }
+            }
+        }
+        if let Some(features) = &scenario.features {
+            for (feature_name, feature) in features.iter() {
+                let feature_id = ElementId::new(ElementKind::Feature, feature_name);
+                index.add_element(feature_id.to_owned());
+                index.add_references(
+                    &feature_id,
+                    ElementKind::Feature,
+                    feature.dependencies.iter().flatten(),
+                );
+                index.add_references(
+                    &feature_id,
+                    ElementKind::Vulnerability,
+                    feature.vulnerabilities.iter().flatten(),
+                );
+            }
+        }
+        if let Some(conditions) = &scenario.conditions {
+            for (condition_name, condition) in conditions.iter() {
+                let condition_id = ElementId::new(ElementKind::Condition, condition_name);
+                index.add_element(condition_id.to_owned());
+                index.add_references(
+                    &condition_id,
+                    ElementKind::Condition,
+                    condition.referenced_conditions().iter(),
+                );
+            }
+        }
+        if let Some(vulnerabilities) = &scenario.vulnerabilities {
+            for vulnerability_name in vulnerabilities.keys() {
+                index.add_element(ElementId::new(
+                    ElementKind::Vulnerability,
+                    vulnerability_name,
+                ));
+            }
+        }
+        if let Some(metrics) = &scenario.metrics {
+            for (metric_name, metric) in metrics.iter() {
+                let metric_id = ElementId::new(ElementKind::Metric, metric_name);
+                index.add_element(metric_id.to_owned());
+                index.add_references(&metric_id, ElementKind::Condition, metric.condition.iter());
+            }
+        }
+        if let Some(evaluations) = &scenario.evaluations {
+            for (evaluation_name, evaluation) in evaluations.iter() {
+                let evaluation_id = ElementId::new(ElementKind::Evaluation, evaluation_name);
+                index.add_element(evaluation_id.to_owned());
+                index.add_references(
+                    &evaluation_id,
+                    ElementKind::Metric,
+                    evaluation.metrics.iter(),
+                );
+            }
+        }
+        if let Some(tlos) = &scenario.tlos {
+            for (tlo_name, tlo) in tlos.iter() {
+                let tlo_id = ElementId::new(ElementKind::Tlo, tlo_name);
+                index.add_element(tlo_id.to_owned());
+                index.add_references(&tlo_id, ElementKind::Evaluation, [&tlo.evaluation]);
+            }
+        }
+        if let Some(goals) = &scenario.goals {
+            for (goal_name, goal) in goals.iter() {
+                let goal_id = ElementId::new(ElementKind::Goal, goal_name);
+                index.add_element(goal_id.to_owned());
+                index.add_references(&goal_id, ElementKind::Tlo, goal.tlos.iter());
+            }
+        }
+        if let Some(entities) = &scenario.entities {
+            for (entity_name, entity) in entities.flatten().iter() {
+                let entity_id = ElementId::new(ElementKind::Entity, entity_name);
+                index.add_element(entity_id.to_owned());
+                index.add_references(&entity_id, ElementKind::Tlo, entity.tlos.iter().flatten());
+                index.add_references(
+                    &entity_id,
+                    ElementKind::Vulnerability,
+                    entity.vulnerabilities.iter().flatten(),
+                );
+                index.add_references(
+                    &entity_id,
+                    ElementKind::Event,
+                    entity.events.iter().flatten(),
+                );
+            }
+        }
+        if let Some(injects) = &scenario.injects {
+            for (inject_name, inject) in injects.iter() {
+                let inject_id = ElementId::new(ElementKind::Inject, inject_name);
+                index.add_element(inject_id.to_owned());
+                index.add_references(&inject_id, ElementKind::Entity, inject.from_entity.iter());
+                index.add_references(
+                    &inject_id,
+                    ElementKind::Entity,
+                    inject.to_entities.iter().flatten(),
+                );
+                index.add_references(&inject_id, ElementKind::Tlo, inject.tlos.iter().flatten());
+                index.add_references(
+                    &inject_id,
+                    ElementKind::Node,
+                    inject
+                        .channel
+                        .iter()
+                        .flat_map(|channel| channel.node.iter()),
+                );
+                for response in inject.expected_responses.iter().flat_map(HashMap::values) {
+                    index.add_references(&inject_id, ElementKind::Metric, response.metric.iter());
+                    index.add_references(&inject_id, ElementKind::Tlo, response.tlo.iter());
+                    index.add_references(
+                        &inject_id,
+                        ElementKind::Condition,
+                        response.condition.iter(),
+                    );
+                }
+            }
+        }
+        if let Some(events) = &scenario.events {
+            for (event_name, event) in events.iter() {
+                let event_id = ElementId::new(ElementKind::Event, event_name);
+                index.add_element(event_id.to_owned());
+                index.add_references(
+                    &event_id,
+                    ElementKind::Condition,
+                    event.conditions.iter().flatten(),
+                );
+                index.add_references(
+                    &event_id,
+                    ElementKind::Inject,
+                    event.injects.iter().flatten(),
+                );
+                index.add_references(&event_id, ElementKind::Event, event.triggered_after());
+            }
+        }
+        if let Some(scripts) = &scenario.scripts {
+            for (script_name, script) in scripts.iter() {
+                let script_id = ElementId::new(ElementKind::Script, script_name);
+                index.add_element(script_id.to_owned());
+                index.add_references(&script_id, ElementKind::Event, script.events.keys());
+            }
+        }
+        if let Some(stories) = &scenario.stories {
+            for (story_name, story) in stories.iter() {
+                let story_id = ElementId::new(ElementKind::Story, story_name);
+                index.add_element(story_id.to_owned());
+                index.add_references(&story_id, ElementKind::Script, story.scripts.iter());
+            }
+        }
+        index
+    }
+
+    fn add_element(&mut self, element_id: ElementId) {
+        self.elements.insert(element_id);
+    }
+
+    fn add_references<'a>(
+        &mut self,
+        element_id: &ElementId,
+        kind: ElementKind,
+        names: impl IntoIterator<Item = &'a String>,
+    ) {
+        for name in names {
+            let referenced_id = ElementId::new(kind, name);
+            self.references
+                .entry(element_id.to_owned())
+                .or_default()
+                .insert(referenced_id.to_owned());
+            self.referenced_by
+                .entry(referenced_id)
+                .or_default()
+                .insert(element_id.to_owned());
+        }
+    }
+
+    pub fn contains(&self, kind: ElementKind, name: &str) -> bool {
+        self.elements.contains(&ElementId::new(kind, name))
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &ElementId> {
+        self.elements.iter()
+    }
+
+    pub fn references(&self, kind: ElementKind, name: &str) -> Vec<ElementId> {
+        self.references
+            .get(&ElementId::new(kind, name))
+            .map(|element_ids| element_ids.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn referenced_by(&self, kind: ElementKind, name: &str) -> Vec<ElementId> {
+        self.referenced_by
+            .get(&ElementId::new(kind, name))
+            .map(|element_ids| element_ids.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn dangling_references(&self) -> Vec<(ElementId, ElementId)> {
+        let mut dangling_references = self
+            .references
+            .iter()
+            .flat_map(|(element_id, referenced_ids)| {
+                referenced_ids
+                    .iter()
+                    .filter(|referenced_id| !self.elements.contains(referenced_id))
+                    .map(|referenced_id| (element_id.to_owned(), referenced_id.to_owned()))
+            })
+            .collect::<Vec<_>>();
+        dangling_references.sort();
+        dangling_references
+    }
+
+    pub fn referenced_by_kind(
+        &self,
+        kind: ElementKind,
+        name: &str,
+        referrer_kind: ElementKind,
+    ) -> Vec<String> {
+        self.referenced_by(kind, name)
+            .into_iter()
+            .filter(|element_id| element_id.kind == referrer_kind)
+            .map(|element_id| element_id.name)
+            .collect()
+    }
+}
+
+impl Scenario {
+    pub fn reference_index(&self) -> ReferenceIndex {
+        ReferenceIndex::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        nodes:
+            web:
+                type: VM
+                source: web-server
+                resources:
+                    cpu: 1
+                    ram: 1 gib
+                roles:
+                    admin:
+                        username: root
+                        entities:
+                            - blue-team
+                vulnerabilities:
+                    - vulnerability-1
+                injects:
+                    inject-1: admin
+            mail:
+                type: VM
+                source: mail-server
+                resources:
+                    cpu: 1
+                    ram: 1 gib
+                vulnerabilities:
+                    - vulnerability-1
+        vulnerabilities:
+            vulnerability-1:
+                name: Weak password
+                description: Admin password is guessable
+                technical: false
+                class: CWE-521
+        conditions:
+            condition-1:
+                command: executable/path.sh
+                interval: 30
+        entities:
+            blue-team:
+                role: Blue
+            red-team:
+                role: Red
+        injects:
+            inject-1:
+                source: inject-package
+                from-entity: red-team
+                to-entities:
+                    - blue-team
+        events:
+            event-1:
+                conditions:
+                    - condition-1
+                injects:
+                    - inject-1
+        scripts:
+            script-1:
+                start-time: 0
+                end-time: 3 hour
+                speed: 1
+                events:
+                    event-1: 1 hour
+        stories:
+            story-1:
+                scripts:
+                    - script-1
+    "#;
+
+    #[test]
+    fn answers_who_uses_an_element() {
+        let index = parse_sdl(SDL).unwrap().reference_index();
+
+        assert_eq!(
+            index.referenced_by_kind(
+                ElementKind::Vulnerability,
+                "vulnerability-1",
+                ElementKind::Node
+            ),
+            vec!["mail".to_string(), "web".to_string()]
+        );
+        assert_eq!(
+            index.referenced_by(ElementKind::Inject, "inject-1"),
+            vec![
+                ElementId::new(ElementKind::Node, "web"),
+                ElementId::new(ElementKind::Event, "event-1"),
+            ]
+        );
+        assert_eq!(
+            index.referenced_by_kind(ElementKind::Script, "script-1", ElementKind::Story),
+            vec!["story-1".to_string()]
+        );
+        assert!(index.contains(ElementKind::Entity, "red-team"));
+        assert!(!index.contains(ElementKind::Entity, "green-team"));
+    }
+
+    #[test]
+    fn answers_what_an_element_references() {
+        let index = parse_sdl(SDL).unwrap().reference_index();
+        insta::assert_yaml_snapshot!(index.references(ElementKind::Inject, "inject-1"));
+    }
+
+    #[test]
+    fn lists_dangling_references() {
+        let mut scenario = parse_sdl(SDL).unwrap();
+        assert!(scenario.reference_index().dangling_references().is_empty());
+
+        scenario
+            .stories
+            .as_mut()
+            .unwrap()
+            .get_mut("story-1")
+            .unwrap()
+            .scripts
+            .push("script-2".to_string());
+        assert_eq!(
+            scenario.reference_index().dangling_references(),
+            vec![(
+                ElementId::new(ElementKind::Story, "story-1"),
+                ElementId::new(ElementKind::Script, "script-2")
+            )]
+        );
+    }
+}
@@ -0,0 +1,837 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+use crate::{
+    constants::MAX_LONG_NAME,
+    entity::{Entities, Entity},
+    inject::ExpectedResponse,
+    node::{NodeType, VM},
+    parse_sdl,
+    reference::ElementKind,
+    Scenario,
+};
+
+fn rename_key<V>(map: &mut HashMap<String, V>, old_name: &str, new_name: &str) {
+    if let Some(value) = map.remove(old_name) {
+        map.insert(new_name.to_owned(), value);
+    }
+}
+
+fn rename_names<'a>(
+    names: impl IntoIterator<Item = &'a mut String>,
+    old_name: &str,
+    new_name: &str,
+) {
+    for name in names {
+        if name == old_name {
+            *name = new_name.to_owned();
+        }
+    }
+}
+
+fn renamed_entity_reference(name: &str, old_name: &str, new_name: &str) -> Option<String> {
+    if name == old_name {
+        return Some(new_name.to_owned());
+    }
+    name.strip_prefix(old_name)
+        .filter(|rest| rest.starts_with('.'))
+        .map(|rest| format!("{new_name}{rest}"))
+}
+
+fn rename_entity_references<'a>(
+    names: impl IntoIterator<Item = &'a mut String>,
+    old_name: &str,
+    new_name: &str,
+) {
+    for name in names {
+        if let Some(renamed) = renamed_entity_reference(name, old_name, new_name) {
+            *name = renamed;
+        }
+    }
+}
+
+fn parent_entities<'a>(entities: &'a mut Entities, path: &[&str]) -> Option<&'a mut Entities> {
+    match path.split_first() {
+        Some((entity_name, rest)) => entities
+            .get_mut(*entity_name)
+            .and_then(|entity| entity.entities.as_mut())
+            .and_then(|child_entities| parent_entities(child_entities, rest)),
+        None => Some(entities),
+    }
+}
+
+fn for_each_entity(entities: &mut Entities, action: &mut impl FnMut(&mut Entity)) {
+    for entity in entities.values_mut() {
+        action(entity);
+        if let Some(child_entities) = &mut entity.entities {
+            for_each_entity(child_entities, action);
+        }
+    }
+}
+
+impl Scenario {
+    pub fn rename(&mut self, kind: ElementKind, old_name: &str, new_name: &str) -> Result<()> {
+        if old_name == new_name {
+            return Ok(());
+        }
+        let index = self.reference_index();
+        if !index.contains(kind, old_name) {
+            return Err(anyhow!("{kind} \"{old_name}\" not found under Scenario"));
+        }
+        if index.contains(kind, new_name) {
+            return Err(anyhow!(
+                "{kind} \"{new_name}\" already exists under Scenario"
+            ));
+        }
+        if new_name.trim().is_empty() {
+            return Err(anyhow!(
+                "{kind} \"{old_name}\" can not be renamed to an empty name"
+            ));
+        }
+
+        match kind {
+            ElementKind::Node => {
+                if new_name.len() > MAX_LONG_NAME {
+                    return Err(anyhow!(
+                        "{} is too long, maximum node name length is {:?}",
+                        new_name,
+                        MAX_LONG_NAME
+                    ));
+                }
+                if let Some(nodes) = &mut self.nodes {
+                    rename_key(nodes, old_name, new_name);
+                }
+                if let Some(infrastructure) = &mut self.infrastructure {
+                    rename_key(infrastructure, old_name, new_name);
+                    for infra_node in infrastructure.values_mut() {
+                        for node_names in [
+                            &mut infra_node.links,
+                            &mut infra_node.dependencies,
+                            &mut infra_node.affinity,
+                            &mut infra_node.anti_affinity,
+                        ] {
+                            rename_names(node_names.iter_mut().flatten(), old_name, new_name);
+                        }
+                    }
+                }
+                if let Some(injects) = &mut self.injects {
+                    for inject in injects.values_mut() {
+                        if let Some(channel) = &mut inject.channel {
+                            rename_names(channel.node.iter_mut(), old_name, new_name);
+                        }
+                    }
+                }
+            }
+            ElementKind::Feature => {
+                if let Some(features) = &mut self.features {
+                    rename_key(features, old_name, new_name);
+                    for feature in features.values_mut() {
+                        rename_names(
+                            feature.dependencies.iter_mut().flatten(),
+                            old_name,
+                            new_name,
+                        );
+                    }
+                }
+                self.for_each_vm(|vm| rename_key(&mut vm.features, old_name, new_name));
+            }
+            ElementKind::Condition => {
+                if let Some(conditions) = &mut self.conditions {
+                    rename_key(conditions, old_name, new_name);
+                    for condition in conditions.values_mut() {
+                        rename_names(condition.all_of.iter_mut().flatten(), old_name, new_name);
+                        rename_names(condition.any_of.iter_mut().flatten(), old_name, new_name);
+                        rename_names(condition.not.iter_mut(), old_name, new_name);
+                    }
+                }
+                self.for_each_vm(|vm| rename_key(&mut vm.conditions, old_name, new_name));
+                if let Some(metrics) = &mut self.metrics {
+                    for metric in metrics.values_mut() {
+                        rename_names(metric.condition.iter_mut(), old_name, new_name);
+                    }
+                }
+                if let Some(events) = &mut self.events {
+                    for event in events.values_mut() {
+                        rename_names(event.conditions.iter_mut().flatten(), old_name, new_name);
+                    }
+                }
+                self.for_each_expected_response(|response| {
+                    rename_names(response.condition.iter_mut(), old_name, new_name)
+                });
+            }
+            ElementKind::Vulnerability => {
+                if let Some(vulnerabilities) = &mut self.vulnerabilities {
+                    rename_key(vulnerabilities, old_name, new_name);
+                }
+                self.for_each_vm(|vm| {
+                    rename_names(vm.vulnerabilities.iter_mut(), old_name, new_name)
+                });
+                if let Some(features) = &mut self.features {
+                    for feature in features.values_mut() {
+                        rename_names(
+                            feature.vulnerabilities.iter_mut().flatten(),
+                            old_name,
+                            new_name,
+                        );
+                    }
+                }
+                if let Some(entities) = &mut self.entities {
+                    for_each_entity(entities, &mut |entity| {
+                        rename_names(
+                            entity.vulnerabilities.iter_mut().flatten(),
+                            old_name,
+                            new_name,
+                        )
+                    });
+                }
+            }
+            ElementKind::Metric => {
+                if let Some(metrics) = &mut self.metrics {
+                    rename_key(metrics, old_name, new_name);
+                }
+                if let Some(evaluations) = &mut self.evaluations {
+                    for evaluation in evaluations.values_mut() {
+                        rename_names(evaluation.metrics.iter_mut(), old_name, new_name);
+                        if let Some(weights) = &mut evaluation.weights {
+                            rename_key(weights, old_name, new_name);
+                        }
+                    }
+                }
+                self.for_each_expected_response(|response| {
+                    rename_names(response.metric.iter_mut(), old_name, new_name)
+                });
+            }
+            ElementKind::Evaluation => {
+                if let Some(evaluations) = &mut self.evaluations {
+                    rename_key(evaluations, old_name, new_name);
+                }
+                if let Some(tlos) = &mut self.tlos {
+                    for tlo in tlos.values_mut() {
+                        rename_names([&mut tlo.evaluation], old_name, new_name);
+                    }
+                }
+            }
+            ElementKind::Tlo => {
+                if let Some(tlos) = &mut self.tlos {
+                    rename_key(tlos, old_name, new_name);
+                }
+                if let Some(goals) = &mut self.goals {
+                    for goal in goals.values_mut() {
+                        rename_names(goal.tlos.iter_mut(), old_name, new_name);
+                    }
+                }
+                if let Some(entities) = &mut self.entities {
+                    for_each_entity(entities, &mut |entity| {
+                        rename_names(entity.tlos.iter_mut().flatten(), old_name, new_name)
+                    });
+                }
+                if let Some(injects) = &mut self.injects {
+                    for inject in injects.values_mut() {
+                        rename_names(inject.tlos.iter_mut().flatten(), old_name, new_name);
+                    }
+                }
+                self.for_each_expected_response(|response| {
+                    rename_names(response.tlo.iter_mut(), old_name, new_name)
+                });
+            }
+            ElementKind::Goal => {
+                if let Some(goals) = &mut self.goals {
+                    rename_key(goals, old_name, new_name);
+                }
+            }
+            ElementKind::Entity => {
+                let old_parent = old_name.rsplit_once('.').map(|(parent, _)| parent);
+                let new_parent = new_name.rsplit_once('.').map(|(parent, _)| parent);
+                if old_parent != new_parent {
+                    return Err(anyhow!(
+                        "Entity \"{old_name}\" can only be renamed under the same parent Entity"
+                    ));
+                }
+                let parent_path = old_parent
+                    .map(|parent| parent.split('.').collect::<Vec<&str>>())
+                    .unwrap_or_default();
+                let old_key = old_name.rsplit('.').next().unwrap_or(old_name);
+                let new_key = new_name.rsplit('.').next().unwrap_or(new_name);
+                if let Some(entities) = self
+                    .entities
+                    .as_mut()
+                    .and_then(|entities| parent_entities(entities, &parent_path))
+                {
+                    rename_key(entities, old_key, new_key);
+                }
+                self.for_each_vm(|vm| {
+                    for role in vm.roles.iter_mut().flat_map(|roles| roles.values_mut()) {
+                        rename_entity_references(
+                            role.entities.iter_mut().flatten(),
+                            old_name,
+                            new_name,
+                        );
+                    }
+                });
+                if let Some(injects) = &mut self.injects {
+                    for inject in injects.values_mut() {
+                        rename_entity_references(inject.from_entity.iter_mut(), old_name, new_name);
+                        rename_entity_references(
+                            inject.to_entities.iter_mut().flatten(),
+                            old_name,
+                            new_name,
+                        );
+                    }
+                }
+            }
+            ElementKind::Inject => {
+                if let Some(injects) = &mut self.injects {
+                    rename_key(injects, old_name, new_name);
+                }
+                self.for_each_vm(|vm| rename_key(&mut vm.injects, old_name, new_name));
+                if let Some(events) = &mut self.events {
+                    for event in events.values_mut() {
+                        rename_names(event.injects.iter_mut().flatten(), old_name, new_name);
+                    }
+                }
+            }
+            ElementKind::Event => {
+                if let Some(events) = &mut self.events {
+                    rename_key(events, old_name, new_name);
+                    for event in events.values_mut() {
+                        rename_names(
+                            event
+                                .trigger
+                                .iter_mut()
+                                .flat_map(|trigger| trigger.event.iter_mut()),
+                            old_name,
+                            new_name,
+                        );
+                    }
+                }
+                if let Some(entities) = &mut self.entities {
+                    for_each_entity(entities, &mut |entity| {
+                        rename_names(entity.events.iter_mut().flatten(), old_name, new_name)
+                    });
+                }
+                if let Some(scripts) = &mut self.scripts {
+                    for script in scripts.values_mut() {
+                        rename_key(&mut script.events, old_name, new_name);
+                    }
+                }
+            }
+            ElementKind::Script => {
+                if let Some(scripts) = &mut self.scripts {
+                    rename_key(scripts, old_name, new_name);
+                }
+                if let Some(stories) = &mut self.stories {
+                    for story in stories.values_mut() {
+                        rename_names(story.scripts.iter_mut(), old_name, new_name);
+                    }
+                }
+            }
+            ElementKind::Story => {
+                if let Some(stories) = &mut self.stories {
+                    rename_key(stories, old_name, new_name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn for_each_vm(&mut self, mut action: impl FnMut(&mut VM)) {
+        if let Some(nodes) = &mut self.nodes {
+            for node in nodes.values_mut() {
+                if let NodeType::VM(vm) = &mut node.type_field {
+                    action(vm);
+                }
+            }
+        }
+    }
+
+    fn for_each_expected_response(&mut self, mut action: impl FnMut(&mut ExpectedResponse)) {
+        if let Some(injects) = &mut self.injects {
+            for inject in injects.values_mut() {
+                for response in inject
+                    .expected_responses
+                    .iter_mut()
+                    .flat_map(|responses| responses.values_mut())
+                {
+                    action(response);
+                }
+            }
+        }
+    }
+}
+
+enum NamePosition {
+    Element(ElementKind),
+    EntityKey(String),
+    EntityReference,
+}
+
+fn matches_any(key: &str, keywords: &[&str]) -> bool {
+    keywords
+        .iter()
+        .any(|keyword| key.eq_ignore_ascii_case(keyword))
+}
+
+fn entity_path(path: &[String]) -> Option<(String, &[String])> {
+    if path.len() < 2 || !matches_any(&path[0], &["entities"]) {
+        return None;
+    }
+    let mut entity_names = vec![path[1].to_owned()];
+    let mut rest = &path[2..];
+    while rest.len() >= 2 && matches_any(&rest[0], &["entities"]) {
+        entity_names.push(rest[1].to_owned());
+        rest = &rest[2..];
+    }
+    Some((entity_names.join("."), rest))
+}
+
+fn key_position(parent_path: &[String]) -> Option<NamePosition> {
+    let path = parent_path
+        .iter()
+        .map(|key| key.to_ascii_lowercase())
+        .collect::<Vec<String>>();
+    let path = path.iter().map(String::as_str).collect::<Vec<&str>>();
+    let kind = match path.as_slice() {
+        ["nodes" | "infrastructure"] => ElementKind::Node,
+        ["features"] | ["nodes", _, "features"] => ElementKind::Feature,
+        ["conditions"] | ["nodes", _, "conditions"] => ElementKind::Condition,
+        ["vulnerabilities"] => ElementKind::Vulnerability,
+        ["metrics"] | ["evaluations", _, "weights"] => ElementKind::Metric,
+        ["evaluations"] => ElementKind::Evaluation,
+        ["tlos"] => ElementKind::Tlo,
+        ["goals"] => ElementKind::Goal,
+        ["injects"] | ["nodes", _, "injects"] => ElementKind::Inject,
+        ["events"] | ["scripts", _, "events"] => ElementKind::Event,
+        ["scripts"] => ElementKind::Script,
+        ["stories"] => ElementKind::Story,
+        ["entities"] => return Some(NamePosition::EntityKey(String::new())),
+        _ => {
+            return entity_path(parent_path).and_then(|(entity_name, rest)| {
+                (rest.len() == 1 && matches_any(&rest[0], &["entities"]))
+                    .then_some(NamePosition::EntityKey(format!("{entity_name}.")))
+            })
+        }
+    };
+    Some(NamePosition::Element(kind))
+}
+
+fn value_position(value_path: &[String]) -> Option<NamePosition> {
+    let path = value_path
+        .iter()
+        .map(|key| key.to_ascii_lowercase())
+        .collect::<Vec<String>>();
+    let path = path.iter().map(String::as_str).collect::<Vec<&str>>();
+    let kind = match path.as_slice() {
+        ["infrastructure", _, "links" | "dependencies" | "affinity" | "anti-affinity"] => {
+            ElementKind::Node
+        }
+        ["features", _, "dependencies"] => ElementKind::Feature,
+        ["conditions", _, "all-of" | "any-of" | "not"]
+        | ["metrics", _, "condition"]
+        | ["events", _, "conditions"] => ElementKind::Condition,
+        ["nodes", _, "vulnerabilities"] | ["features", _, "vulnerabilities"] => {
+            ElementKind::Vulnerability
+        }
+        ["evaluations", _, "metrics"] => ElementKind::Metric,
+        ["tlos", _, "evaluation"] => ElementKind::Evaluation,
+        ["goals", _, "tlos"] | ["injects", _, "tlos"] => ElementKind::Tlo,
+        ["injects", _, "channel", "node"] => ElementKind::Node,
+        ["events", _, "injects"] => ElementKind::Inject,
+        ["events", _, "trigger", "event"] => ElementKind::Event,
+        ["injects", _, "expected-responses", _, "metric"] => ElementKind::Metric,
+        ["injects", _, "expected-responses", _, "tlo"] => ElementKind::Tlo,
+        ["injects", _, "expected-responses", _, "condition"] => ElementKind::Condition,
+        ["stories", _, "scripts"] => ElementKind::Script,
+        ["nodes", _, "roles", _, "entities" | "entity"]
+        | ["injects", _, "from-entity" | "to-entities"] => {
+            return Some(NamePosition::EntityReference)
+        }
+        _ => {
+            let (_, rest) = entity_path(value_path)?;
+            match rest {
+                [key] if matches_any(key, &["tlos"]) => ElementKind::Tlo,
+                [key] if matches_any(key, &["vulnerabilities"]) => ElementKind::Vulnerability,
+                [key] if matches_any(key, &["events"]) => ElementKind::Event,
+                _ => return None,
+            }
+        }
+    };
+    Some(NamePosition::Element(kind))
+}
+
+fn split_comment(content: &str) -> (&str, &str) {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, character) in content.char_indices() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open_quote), _) if character == open_quote => quote = None,
+            (None, '#') if previous.is_whitespace() => {
+                return content.split_at(index);
+            }
+            _ => {}
+        }
+        previous = character;
+    }
+    (content, "")
+}
+
+fn mapping_separator(code: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut characters = code.char_indices().peekable();
+    while let Some((index, character)) = characters.next() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open_quote), _) if character == open_quote => quote = None,
+            (None, ':')
+                if characters
+                    .peek()
+                    .is_none_or(|(_, next)| next.is_whitespace()) =>
+            {
+                return Some(index);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unquote(scalar: &str) -> (Option<char>, &str) {
+    let mut characters = scalar.chars();
+    match (characters.next(), characters.next_back()) {
+        (Some(first), Some(last)) if first == last && (first == '"' || first == '\'') => {
+            (Some(first), &scalar[1..scalar.len() - 1])
+        }
+        _ => (None, scalar),
+    }
+}
+
+fn replace_scalar(text: &str, rename: &impl Fn(&str) -> Option<String>) -> Option<String> {
+    let leading = text.len() - text.trim_start().len();
+    let scalar = text.trim();
+    let (quote, value) = unquote(scalar);
+    let renamed = rename(value)?;
+    let renamed = match quote {
+        Some(quote) => format!("{quote}{renamed}{quote}"),
+        None => renamed,
+    };
+    Some(format!(
+        "{}{}{}",
+        &text[..leading],
+        renamed,
+        &text[leading + scalar.len()..]
+    ))
+}
+
+fn replace_value(text: &str, rename: &impl Fn(&str) -> Option<String>) -> Option<String> {
+    let trimmed = text.trim();
+    if trimmed.starts_with('[') && trimmed.ends_with(']') {
+        let leading = text.len() - text.trim_start().len();
+        let items = &trimmed[1..trimmed.len() - 1];
+        let renamed_items = items
+            .split(',')
+            .map(|item| replace_scalar(item, rename).unwrap_or_else(|| item.to_owned()))
+            .collect::<Vec<String>>()
+            .join(",");
+        if renamed_items == items {
+            return None;
+        }
+        return Some(format!(
+            "{}[{}]{}",
+            &text[..leading],
+            renamed_items,
+            &text[leading + trimmed.len()..]
+        ));
+    }
+    replace_scalar(text, rename)
+}
+
+struct TextRename<'a> {
+    kind: ElementKind,
+    old_name: &'a str,
+    new_name: &'a str,
+}
+
+impl TextRename<'_> {
+    fn renamed(&self, position: Option<NamePosition>, value: &str) -> Option<String> {
+        match position? {
+            NamePosition::Element(kind) if kind == self.kind && value == self.old_name => {
+                Some(self.new_name.to_owned())
+            }
+            NamePosition::EntityKey(prefix) if self.kind == ElementKind::Entity => {
+                (format!("{prefix}{value}") == self.old_name)
+                    .then(|| self.new_name.rsplit('.').next().unwrap_or(self.new_name))
+                    .map(str::to_owned)
+            }
+            NamePosition::EntityReference if self.kind == ElementKind::Entity => {
+                renamed_entity_reference(value, self.old_name, self.new_name)
+            }
+            _ => None,
+        }
+    }
+
+    fn rename_mapping(&self, code: &str, path: &[String]) -> (String, String) {
+        let separator = mapping_separator(code).unwrap_or(code.len());
+        let (key_text, value_text) = code.split_at(separator);
+        let key = unquote(key_text.trim()).1.to_owned();
+
+        let renamed_key =
+            replace_scalar(key_text, &|value| self.renamed(key_position(path), value))
+                .unwrap_or_else(|| key_text.to_owned());
+
+        let mut value_path = path.to_vec();
+        value_path.push(key.to_owned());
+        let renamed_value = match value_text.strip_prefix(':') {
+            Some(value) if !value.trim().is_empty() => format!(
+                ":{}",
+                replace_value(value, &|value| self
+                    .renamed(value_position(&value_path), value))
+                .unwrap_or_else(|| value.to_owned())
+            ),
+            _ => value_text.to_owned(),
+        };
+        (format!("{renamed_key}{renamed_value}"), key)
+    }
+
+    fn rename_text(&self, sdl_string: &str) -> String {
+        let mut output = String::with_capacity(sdl_string.len());
+        let mut stack: Vec<(usize, String)> = vec![];
+        let mut block_scalar_indent: Option<usize> = None;
+
+        for line in sdl_string.split_inclusive('\n') {
+            let body = line.trim_end_matches(['\n', '\r']);
+            let line_ending = &line[body.len()..];
+            let indent = body.len() - body.trim_start_matches(' ').len();
+            let content = &body[indent..];
+
+            if let Some(scalar_indent) = block_scalar_indent {
+                if content.trim().is_empty() || indent > scalar_indent {
+                    output.push_str(line);
+                    continue;
+                }
+                block_scalar_indent = None;
+            }
+            if content.trim().is_empty()
+                || content.starts_with('#')
+                || content.starts_with("---")
+                || content.starts_with("...")
+            {
+                output.push_str(line);
+                continue;
+            }
+
+            let (code, comment) = split_comment(content);
+            let renamed_code = if let Some(item) = code
+                .strip_prefix('-')
+                .filter(|item| item.is_empty() || item.starts_with(' '))
+            {
+                while stack
+                    .last()
+                    .is_some_and(|(entry_indent, _)| *entry_indent > indent)
+                {
+                    stack.pop();
+                }
+                let item_offset = 1 + item.len() - item.trim_start().len();
+                let item_code = &code[item_offset..];
+                if mapping_separator(item_code).is_some() {
+                    let item_indent = indent + item_offset;
+                    let path = stack
+                        .iter()
+                        .map(|(_, key)| key.to_owned())
+                        .collect::<Vec<String>>();
+                    let (renamed_item, key) = self.rename_mapping(item_code, &path);
+                    stack.push((item_indent, key));
+                    format!("{}{}", &code[..item_offset], renamed_item)
+                } else {
+                    let path = stack
+                        .iter()
+                        .map(|(_, key)| key.to_owned())
+                        .collect::<Vec<String>>();
+                    format!(
+                        "-{}",
+                        replace_value(item, &|value| self.renamed(value_position(&path), value))
+                            .unwrap_or_else(|| item.to_owned())
+                    )
+                }
+            } else {
+                while stack
+                    .last()
+                    .is_some_and(|(entry_indent, _)| *entry_indent >= indent)
+                {
+                    stack.pop();
+                }
+                let path = stack
+                    .iter()
+                    .map(|(_, key)| key.to_owned())
+                    .collect::<Vec<String>>();
+                let (renamed_code, key) = self.rename_mapping(code, &path);
+                let value = code
+                    .split_at(mapping_separator(code).unwrap_or(code.len()))
+                    .1
+                    .trim_start_matches(':')
+                    .trim();
+                if value.starts_with('|') || value.starts_with('>') {
+                    block_scalar_indent = Some(indent);
+                }
+                stack.push((indent, key));
+                renamed_code
+            };
+
+            output.push_str(&body[..indent]);
+            output.push_str(&renamed_code);
+            output.push_str(comment);
+            output.push_str(line_ending);
+        }
+        output
+    }
+}
+
+pub fn rename_sdl(
+    sdl_string: &str,
+    kind: ElementKind,
+    old_name: &str,
+    new_name: &str,
+) -> Result<String> {
+    let mut scenario = parse_sdl(sdl_string)?;
+    scenario.rename(kind, old_name, new_name)?;
+
+    let renamed_sdl = TextRename {
+        kind,
+        old_name,
+        new_name,
+    }
+    .rename_text(sdl_string);
+    let renamed_scenario = parse_sdl(&renamed_sdl)?;
+    if serde_yaml::to_value(&renamed_scenario)? != serde_yaml::to_value(&scenario)? {
+        return Err(anyhow!(
+            "Renaming {kind} \"{old_name}\" in the SDL text did not update every reference"
+        ));
+    }
+    Ok(renamed_sdl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDL: &str = r#"
+name: test-scenario
+nodes:
+    web:
+        type: VM
+        source: web-server
+        resources:
+            cpu: 1
+            ram: 1 gib
+        roles:
+            admin:
+                username: root
+                entities:
+                    - blue-team.bob
+        conditions:
+            web-check: admin # runs as root
+infrastructure:
+    web: 1
+conditions:
+    web-check:
+        command: /usr/bin/web-check.sh
+        interval: 30
+        description: |
+            web-check: keep this text as it is
+    web-down:
+        not: "web-check"
+metrics:
+    metric-1:
+        type: CONDITIONAL
+        max-score: 10
+        condition: web-check
+events:
+    event-1:
+        conditions: [web-check, 'web-down']
+entities:
+    blue-team:
+        role: Blue
+        entities:
+            bob:
+                role: Blue
+"#;
+
+    #[test]
+    fn renames_condition_in_sdl_text() {
+        let renamed_sdl =
+            rename_sdl(SDL, ElementKind::Condition, "web-check", "web-health").unwrap();
+        insta::assert_snapshot!(renamed_sdl);
+    }
+
+    #[test]
+    fn renames_nested_entity_and_references() {
+        let mut scenario = parse_sdl(SDL).unwrap();
+        scenario
+            .rename(ElementKind::Entity, "blue-team", "defenders")
+            .unwrap();
+
+        let entities = scenario.entities.as_ref().unwrap();
+        assert!(entities["defenders"]
+            .entities
+            .as_ref()
+            .unwrap()
+            .contains_key("bob"));
+        let NodeType::VM(vm) = &scenario.nodes.as_ref().unwrap()["web"].type_field else {
+            panic!("Node \"web\" is not a VM");
+        };
+        assert_eq!(
+            vm.roles.as_ref().unwrap()["admin"].entities,
+            Some(vec!["defenders.bob".to_string()])
+        );
+        assert!(rename_sdl(SDL, ElementKind::Entity, "blue-team", "defenders").is_ok());
+        assert!(rename_sdl(SDL, ElementKind::Entity, "blue-team.bob", "blue-team.alice").is_ok());
+    }
+
+    #[test]
+    fn renames_event_trigger_reference_in_sdl_text() {
+        let sdl = SDL.replace(
+            "        conditions: [web-check, 'web-down']\n",
+            "        conditions: [web-check, 'web-down']\n    event-2:\n        trigger:\n            type: after\n            event: event-1\n",
+        );
+        let renamed_sdl = rename_sdl(&sdl, ElementKind::Event, "event-1", "web-alert").unwrap();
+        assert!(renamed_sdl.contains("    web-alert:\n"));
+        assert!(renamed_sdl.contains("            event: web-alert\n"));
+    }
+
+    #[test]
+    fn renames_inject_channel_node() {
+        let sdl = format!(
+            "{SDL}injects:\n    dropped-file:\n        source: inject-package\n        channel:\n            type: file-drop\n            node: web\n            path: /tmp/readme.txt\n"
+        );
+        let mut scenario = parse_sdl(&sdl).unwrap();
+        scenario.rename(ElementKind::Node, "web", "www").unwrap();
+
+        let channel = scenario.injects.as_ref().unwrap()["dropped-file"]
+            .channel
+            .as_ref()
+            .unwrap();
+        assert_eq!(channel.node, Some("www".to_string()));
+        let renamed_sdl = rename_sdl(&sdl, ElementKind::Node, "web", "www").unwrap();
+        assert!(renamed_sdl.contains("            node: www\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Condition \"web-down\" already exists under Scenario")]
+    fn rename_collision_is_rejected() {
+        let mut scenario = parse_sdl(SDL).unwrap();
+        scenario
+            .rename(ElementKind::Condition, "web-check", "web-down")
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "is too long, maximum node name length is 35")]
+    fn too_long_node_name_is_rejected() {
+        rename_sdl(
+            SDL,
+            ElementKind::Node,
+            "web",
+            "a-very-long-web-server-name-that-breaks-limits",
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,349 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Days, Duration, FixedOffset, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::Formalize;
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Blackout {
+    #[serde(default, alias = "Start", alias = "START")]
+    pub start: Option<DateTime<FixedOffset>>,
+    #[serde(default, alias = "End", alias = "END")]
+    pub end: Option<DateTime<FixedOffset>>,
+    #[serde(
+        default,
+        rename = "daily-start",
+        alias = "Daily-start",
+        alias = "DAILY-START"
+    )]
+    pub daily_start: Option<NaiveTime>,
+    #[serde(
+        default,
+        rename = "daily-end",
+        alias = "Daily-end",
+        alias = "DAILY-END"
+    )]
+    pub daily_end: Option<NaiveTime>,
+    #[serde(alias = "Description", alias = "DESCRIPTION")]
+    pub description: Option<String>,
+}
+
+fn resolve_local_time(local_time: NaiveDateTime, time_zone: &Tz) -> Option<DateTime<FixedOffset>> {
+    time_zone
+        .from_local_datetime(&local_time)
+        .earliest()
+        .or_else(|| {
+            time_zone
+                .from_local_datetime(&(local_time + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|moment| moment.fixed_offset())
+}
+
+impl Blackout {
+    fn intervals_around(
+        &self,
+        moment: DateTime<FixedOffset>,
+        time_zone: Option<&Tz>,
+    ) -> Vec<(DateTime<FixedOffset>, DateTime<FixedOffset>)> {
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            return vec![(start, end)];
+        }
+        let (Some(daily_start), Some(daily_end)) = (self.daily_start, self.daily_end) else {
+            return vec![];
+        };
+
+        let resolve = |local_time: NaiveDateTime| match time_zone {
+            Some(time_zone) => resolve_local_time(local_time, time_zone),
+            None => local_time.and_local_timezone(*moment.offset()).single(),
+        };
+        let date = match time_zone {
+            Some(time_zone) => moment.with_timezone(time_zone).date_naive(),
+            None => moment.date_naive(),
+        };
+        [date - Days::new(1), date, date + Days::new(1)]
+            .into_iter()
+            .filter_map(|date| {
+                let end_date = match daily_end <= daily_start {
+                    true => date + Days::new(1),
+                    false => date,
+                };
+                Some((
+                    resolve(date.and_time(daily_start))?,
+                    resolve(end_date.and_time(daily_end))?,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl Formalize for Blackout {
+    fn formalize(&mut self) -> Result<()> {
+        let is_absolute = self.start.is_some() || self.end.is_some();
+        let is_daily = self.daily_start.is_some() || self.daily_end.is_some();
+
+        if is_absolute && is_daily {
+            return Err(anyhow!(
+                "Blackout must have Start and End or Daily-start and Daily-end defined, not both"
+            ));
+        }
+        if is_absolute {
+            match (self.start, self.end) {
+                (Some(start), Some(end)) if start < end => {}
+                (Some(_), Some(_)) => {
+                    return Err(anyhow!("Blackout End must be later than Start"));
+                }
+                _ => {
+                    return Err(anyhow!("Blackout must have both Start and End defined"));
+                }
+            }
+        } else if is_daily {
+            match (self.daily_start, self.daily_end) {
+                (Some(daily_start), Some(daily_end)) if daily_start != daily_end => {}
+                (Some(_), Some(_)) => {
+                    return Err(anyhow!(
+                        "Blackout Daily-start and Daily-end can not be the same time"
+                    ));
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "Blackout must have both Daily-start and Daily-end defined"
+                    ));
+                }
+            }
+        } else {
+            return Err(anyhow!(
+                "Blackout must have Start and End or Daily-start and Daily-end defined"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum HelperStart {
+    Absolute(DateTime<FixedOffset>),
+    Local(NaiveDateTime),
+}
+
+/// Without a Time-zone, daily Blackouts are resolved in the fixed offset of Start and do not
+/// follow daylight saving time changes.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Schedule {
+    #[serde(rename = "start", alias = "Start", alias = "START", skip_serializing)]
+    start_helper: HelperStart,
+    #[serde(default, skip_deserializing)]
+    pub start: DateTime<FixedOffset>,
+    #[serde(
+        default,
+        rename = "time-zone",
+        alias = "Time-zone",
+        alias = "TIME-ZONE"
+    )]
+    pub time_zone: Option<Tz>,
+    #[serde(default, alias = "Blackouts", alias = "BLACKOUTS")]
+    pub blackouts: Option<Vec<Blackout>>,
+    #[serde(alias = "Description", alias = "DESCRIPTION")]
+    pub description: Option<String>,
+}
+
+impl Schedule {
+    fn active_blackout_end(&self, moment: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        self.blackouts
+            .iter()
+            .flatten()
+            .flat_map(|blackout| blackout.intervals_around(moment, self.time_zone.as_ref()))
+            .filter(|(start, end)| *start <= moment && moment < *end)
+            .map(|(_, end)| end)
+            .max()
+    }
+
+    fn next_blackout_start(&self, moment: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        self.blackouts
+            .iter()
+            .flatten()
+            .flat_map(|blackout| blackout.intervals_around(moment, self.time_zone.as_ref()))
+            .map(|(start, _)| start)
+            .filter(|start| *start > moment)
+            .min()
+    }
+
+    fn to_local_offset(&self, moment: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        match &self.time_zone {
+            Some(time_zone) => moment.with_timezone(time_zone).fixed_offset(),
+            None => moment,
+        }
+    }
+
+    pub fn resolve(&self, active_offset: f64) -> DateTime<FixedOffset> {
+        let mut remaining = Duration::milliseconds((active_offset * 1000.0).round() as i64);
+        let mut moment = self.start;
+        loop {
+            while let Some(blackout_end) = self.active_blackout_end(moment) {
+                moment = blackout_end;
+            }
+            match self.next_blackout_start(moment) {
+                Some(blackout_start) if blackout_start - moment < remaining => {
+                    remaining -= blackout_start - moment;
+                    moment = blackout_start;
+                }
+                _ => return self.to_local_offset(moment + remaining),
+            }
+        }
+    }
+}
+
+impl Formalize for Schedule {
+    fn formalize(&mut self) -> Result<()> {
+        self.start = match (&self.start_helper, &self.time_zone) {
+            (HelperStart::Absolute(start), _) => self.to_local_offset(*start),
+            (HelperStart::Local(start), Some(time_zone)) => resolve_local_time(*start, time_zone)
+                .filter(|resolved_start| resolved_start.naive_local() == *start)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Schedule Start \"{start}\" does not exist in Time-zone \"{time_zone}\""
+                    )
+                })?,
+            (HelperStart::Local(start), None) => {
+                return Err(anyhow!(
+                    "Schedule Start \"{start}\" requires an offset or a Time-zone"
+                ));
+            }
+        };
+        if let Some(blackouts) = &mut self.blackouts {
+            for blackout in blackouts.iter_mut() {
+                blackout.formalize()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        schedule:
+            start: 2024-05-06T16:00:00+03:00
+            blackouts:
+                - daily-start: "18:00"
+                  daily-end: "09:00"
+                  description: Overnight pause
+                - start: 2024-05-07T12:00:00+03:00
+                  end: 2024-05-07T13:00:00+03:00
+                  description: Lunch
+        conditions:
+            condition-1:
+                command: executable/path.sh
+                interval: 30
+        injects:
+            phishing-email:
+                source: inject-package
+        events:
+            phishing-wave:
+                conditions:
+                    - condition-1
+                injects:
+                    - phishing-email
+            ransomware:
+                conditions:
+                    - condition-1
+        scripts:
+            main-script:
+                start-time: 0
+                end-time: 8h
+                speed: 1
+                events:
+                    phishing-wave: 1h
+                    ransomware: 6h
+        stories:
+            main-story:
+                scripts:
+                    - main-script
+    "#;
+
+    #[test]
+    fn timeline_is_resolved_to_absolute_timestamps() {
+        let timeline = parse_sdl(SDL).unwrap().timeline().unwrap();
+        insta::assert_yaml_snapshot!(timeline);
+    }
+
+    #[test]
+    fn story_schedule_overrides_scenario_schedule() {
+        let sdl = SDL.replace(
+            "            main-story:\n",
+            "            main-story:\n                schedule:\n                    start: 2024-06-01T10:00:00Z\n",
+        );
+        let timeline = parse_sdl(&sdl).unwrap().timeline().unwrap();
+        assert_eq!(
+            timeline.entries[0].timestamp.unwrap().to_rfc3339(),
+            "2024-06-01T11:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn daily_blackouts_follow_time_zone_across_daylight_saving_change() {
+        let sdl = SDL
+            .replace(
+                "            start: 2024-05-06T16:00:00+03:00\n",
+                "            start: 2024-03-30T16:00:00\n            time-zone: Europe/Tallinn\n",
+            )
+            .replace(
+                "                    phishing-wave: 1h\n",
+                "                    phishing-wave: 3h\n",
+            );
+        let timeline = parse_sdl(&sdl).unwrap().timeline().unwrap();
+        assert_eq!(
+            timeline.entries[0].timestamp.unwrap().to_rfc3339(),
+            "2024-03-31T10:00:00+03:00"
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Schedule Start \"2024-05-06 16:00:00\" requires an offset or a Time-zone"
+    )]
+    fn local_start_requires_time_zone() {
+        let sdl = SDL.replace(
+            "start: 2024-05-06T16:00:00+03:00",
+            "start: 2024-05-06T16:00:00",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Schedule Start \"2024-03-31 03:30:00\" does not exist in Time-zone \"Europe/Tallinn\""
+    )]
+    fn local_start_in_daylight_saving_gap_is_rejected() {
+        let sdl = SDL.replace(
+            "            start: 2024-05-06T16:00:00+03:00\n",
+            "            start: 2024-03-31T03:30:00\n            time-zone: Europe/Tallinn\n",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Blackout End must be later than Start")]
+    fn blackout_ending_before_start_is_rejected() {
+        let sdl = SDL.replace(
+            "end: 2024-05-07T13:00:00+03:00",
+            "end: 2024-05-07T11:00:00+03:00",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    fn daily_blackout_requires_both_times() {
+        let blackout = r#"
+            daily-start: "18:00"
+        "#;
+        let mut blackout = serde_yaml::from_str::<Blackout>(blackout).unwrap();
+        assert!(blackout.formalize().is_err());
+    }
+}
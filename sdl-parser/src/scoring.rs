@@ -0,0 +1,505 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{
+    condition::{Condition, ConditionEvaluation},
+    entity::Flatten,
+    evaluation::Evaluation,
+    metric::Metrics,
+    node::NodeType,
+    Scenario,
+};
+
+pub type MetricScores = HashMap<String, u32>;
+
+pub type EntityScores = HashMap<String, MetricScores>;
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct EvaluationResult {
+    pub score: u32,
+    pub max_score: u32,
+    pub required_score: u32,
+    pub passed: bool,
+    pub explanation: String,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct TloResult {
+    pub evaluation: String,
+    pub achieved: bool,
+    pub explanation: String,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct GoalResult {
+    pub completed: bool,
+    pub explanation: String,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EntityScoreReport {
+    pub evaluations: HashMap<String, EvaluationResult>,
+    pub tlos: HashMap<String, TloResult>,
+    pub goals: HashMap<String, GoalResult>,
+}
+
+pub type ScoreReport = HashMap<String, EntityScoreReport>;
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct EvaluationAchievability {
+    pub max_achievable_score: u32,
+    pub required_score: u32,
+    pub achievable: bool,
+    pub reasons: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EntityAchievability {
+    pub max_achievable_score: u32,
+    pub evaluations: HashMap<String, EvaluationAchievability>,
+    pub unwinnable_evaluations: Vec<String>,
+}
+
+pub type AchievabilityReport = HashMap<String, EntityAchievability>;
+
+impl Scenario {
+    pub fn score(&self, entity_scores: &EntityScores) -> Result<ScoreReport> {
+        let entities = self
+            .entities
+            .as_ref()
+            .map(|entities| entities.flatten())
+            .unwrap_or_default();
+        for (entity_name, metric_scores) in entity_scores.iter() {
+            if !entities.contains_key(entity_name) {
+                return Err(anyhow!(
+                    "Entity \"{entity_name}\" not found under Scenario Entities"
+                ));
+            }
+            self.validate_metric_scores(metric_scores)?;
+        }
+
+        let empty_scores = MetricScores::new();
+        let mut score_report = ScoreReport::new();
+        for (entity_name, entity) in entities.iter() {
+            let Some(entity_tlos) = &entity.tlos else {
+                continue;
+            };
+            let metric_scores = entity_scores.get(entity_name).unwrap_or(&empty_scores);
+            let mut entity_report = EntityScoreReport::default();
+
+            for tlo_name in entity_tlos {
+                let tlo = self
+                    .tlos
+                    .as_ref()
+                    .and_then(|tlos| tlos.get(tlo_name))
+                    .ok_or_else(|| anyhow!("TLO \"{tlo_name}\" not found under Scenario TLOs"))?;
+                let evaluation_name = &tlo.evaluation;
+                if !entity_report.evaluations.contains_key(evaluation_name) {
+                    let evaluation = self
+                        .evaluations
+                        .as_ref()
+                        .and_then(|evaluations| evaluations.get(evaluation_name))
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "Evaluation \"{evaluation_name}\" not found under Scenario Evaluations"
+                            )
+                        })?;
+                    entity_report.evaluations.insert(
+                        evaluation_name.to_owned(),
+                        self.score_evaluation(evaluation, metric_scores)?,
+                    );
+                }
+
+                let evaluation_result = &entity_report.evaluations[evaluation_name];
+                let explanation = match evaluation_result.passed {
+                    true => format!("Evaluation \"{evaluation_name}\" passed"),
+                    false => format!("Evaluation \"{evaluation_name}\" failed"),
+                };
+                entity_report.tlos.insert(
+                    tlo_name.to_owned(),
+                    TloResult {
+                        evaluation: evaluation_name.to_owned(),
+                        achieved: evaluation_result.passed,
+                        explanation,
+                    },
+                );
+            }
+
+            if let Some(goals) = &self.goals {
+                for (goal_name, goal) in goals.iter() {
+                    if !goal
+                        .tlos
+                        .iter()
+                        .any(|tlo_name| entity_tlos.contains(tlo_name))
+                    {
+                        continue;
+                    }
+                    let mut missing_tlos = goal
+                        .tlos
+                        .iter()
+                        .filter(|tlo_name| {
+                            !entity_report
+                                .tlos
+                                .get(*tlo_name)
+                                .is_some_and(|tlo_result| tlo_result.achieved)
+                        })
+                        .map(|tlo_name| format!("\"{tlo_name}\""))
+                        .collect::<Vec<String>>();
+                    missing_tlos.sort();
+                    let explanation = match missing_tlos.is_empty() {
+                        true => "All TLOs achieved".to_string(),
+                        false => format!("TLOs not achieved: {}", missing_tlos.join(", ")),
+                    };
+                    entity_report.goals.insert(
+                        goal_name.to_owned(),
+                        GoalResult {
+                            completed: missing_tlos.is_empty(),
+                            explanation,
+                        },
+                    );
+                }
+            }
+
+            score_report.insert(entity_name.to_owned(), entity_report);
+        }
+        Ok(score_report)
+    }
+
+    pub fn achievability_report(&self) -> Result<AchievabilityReport> {
+        let entities = self
+            .entities
+            .as_ref()
+            .map(|entities| entities.flatten())
+            .unwrap_or_default();
+
+        let mut achievability_report = AchievabilityReport::new();
+        for (entity_name, entity) in entities.iter() {
+            let Some(entity_tlos) = &entity.tlos else {
+                continue;
+            };
+            let mut entity_achievability = EntityAchievability::default();
+            for tlo_name in entity_tlos {
+                let Some(evaluation_name) = self
+                    .tlos
+                    .as_ref()
+                    .and_then(|tlos| tlos.get(tlo_name))
+                    .map(|tlo| &tlo.evaluation)
+                else {
+                    continue;
+                };
+                if entity_achievability
+                    .evaluations
+                    .contains_key(evaluation_name)
+                {
+                    continue;
+                }
+                let Some(evaluation) = self
+                    .evaluations
+                    .as_ref()
+                    .and_then(|evaluations| evaluations.get(evaluation_name))
+                else {
+                    continue;
+                };
+                let evaluation_achievability = self.evaluation_achievability(evaluation)?;
+                entity_achievability.max_achievable_score = entity_achievability
+                    .max_achievable_score
+                    .checked_add(evaluation_achievability.max_achievable_score)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Entity \"{entity_name}\" maximum achievable score can not exceed {}",
+                            u32::MAX
+                        )
+                    })?;
+                if !evaluation_achievability.achievable {
+                    entity_achievability
+                        .unwinnable_evaluations
+                        .push(evaluation_name.to_owned());
+                }
+                entity_achievability
+                    .evaluations
+                    .insert(evaluation_name.to_owned(), evaluation_achievability);
+            }
+            entity_achievability.unwinnable_evaluations.sort();
+            achievability_report.insert(entity_name.to_owned(), entity_achievability);
+        }
+        Ok(achievability_report)
+    }
+
+    fn evaluation_achievability(&self, evaluation: &Evaluation) -> Result<EvaluationAchievability> {
+        let empty_metrics = Metrics::new();
+        let metrics = self.metrics.as_ref().unwrap_or(&empty_metrics);
+        let max_score = evaluation.weighted_max_score(metrics)?;
+        let mut max_achievable_score = 0;
+        let mut reasons = vec![];
+        for metric_name in evaluation.metrics.iter() {
+            let Some(metric) = metrics.get(metric_name) else {
+                continue;
+            };
+            let weighted_max_score =
+                evaluation.weighted_metric_score(metric_name, metric.max_score)?;
+            match &metric.condition {
+                Some(condition_name) if !self.is_condition_reachable(condition_name) => {
+                    reasons.push(format!(
+                        "Metric \"{metric_name}\" Condition \"{condition_name}\" is not attached to any deployed VM"
+                    ));
+                }
+                _ => max_achievable_score += weighted_max_score,
+            }
+        }
+
+        let required_score = evaluation.required_score(max_score);
+        let achievable = max_achievable_score >= required_score;
+        if !achievable {
+            reasons.push(format!(
+                "Required score {required_score} exceeds the maximum achievable score {max_achievable_score}"
+            ));
+        }
+        Ok(EvaluationAchievability {
+            max_achievable_score,
+            required_score,
+            achievable,
+            reasons,
+        })
+    }
+
+    fn is_condition_reachable(&self, condition_name: &str) -> bool {
+        let (Some(nodes), Some(infrastructure)) = (&self.nodes, &self.infrastructure) else {
+            return false;
+        };
+        Condition::evaluate(
+            condition_name,
+            &self.conditions,
+            ConditionEvaluation::Availability,
+            &|condition_name| {
+                nodes
+                    .iter()
+                    .any(|(node_name, node)| match &node.type_field {
+                        NodeType::VM(vm) => {
+                            vm.conditions.contains_key(condition_name)
+                                && infrastructure.contains_key(node_name)
+                        }
+                        _ => false,
+                    })
+            },
+        )
+    }
+
+    fn validate_metric_scores(&self, metric_scores: &MetricScores) -> Result<()> {
+        for (metric_name, score) in metric_scores.iter() {
+            let metric = self
+                .metrics
+                .as_ref()
+                .and_then(|metrics| metrics.get(metric_name))
+                .ok_or_else(|| {
+                    anyhow!("Metric \"{metric_name}\" not found under Scenario Metrics")
+                })?;
+            if *score > metric.max_score {
+                return Err(anyhow!(
+                    "Metric \"{metric_name}\" score {score} is over its max-score {}",
+                    metric.max_score
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn score_evaluation(
+        &self,
+        evaluation: &Evaluation,
+        metric_scores: &MetricScores,
+    ) -> Result<EvaluationResult> {
+        let empty_metrics = Metrics::new();
+        let metrics = self.metrics.as_ref().unwrap_or(&empty_metrics);
+        let max_score = evaluation.weighted_max_score(metrics)?;
+        let mut score = 0;
+        for metric_name in evaluation.metrics.iter() {
+            if !metrics.contains_key(metric_name) {
+                return Err(anyhow!(
+                    "Metric \"{metric_name}\" not found under Scenario Metrics"
+                ));
+            }
+            score += evaluation.weighted_metric_score(
+                metric_name,
+                metric_scores.get(metric_name).copied().unwrap_or_default(),
+            )?;
+        }
+
+        let required_score = evaluation.required_score(max_score);
+        let passed = score >= required_score;
+        let explanation =
+            format!("Scored {score} out of {max_score} points, {required_score} required to pass");
+        Ok(EvaluationResult {
+            score,
+            max_score,
+            required_score,
+            passed,
+            explanation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        conditions:
+            condition-1:
+                command: executable/path.sh
+                interval: 30
+        metrics:
+            metric-1:
+                type: MANUAL
+                artifact: true
+                max-score: 10
+            metric-2:
+                type: CONDITIONAL
+                max-score: 10
+                condition: condition-1
+        evaluations:
+            evaluation-1:
+                metrics:
+                    - metric-1
+                    - metric-2
+                weights:
+                    metric-2: 2
+                min-score: 75
+            evaluation-2:
+                metrics:
+                    - metric-2
+                min-score:
+                    absolute: 5
+        tlos:
+            tlo-1:
+                evaluation: evaluation-1
+            tlo-2:
+                evaluation: evaluation-2
+        goals:
+            goal-1:
+                tlos:
+                    - tlo-1
+                    - tlo-2
+        entities:
+            blue-team:
+                role: Blue
+                tlos:
+                    - tlo-1
+                    - tlo-2
+                entities:
+                    bob:
+                        role: Blue
+                        tlos:
+                            - tlo-2
+    "#;
+
+    #[test]
+    fn scores_evaluations_tlos_and_goals() {
+        let scenario = parse_sdl(SDL).unwrap();
+        let entity_scores = EntityScores::from([
+            (
+                "blue-team".to_string(),
+                MetricScores::from([("metric-1".to_string(), 4), ("metric-2".to_string(), 10)]),
+            ),
+            (
+                "blue-team.bob".to_string(),
+                MetricScores::from([("metric-2".to_string(), 5)]),
+            ),
+        ]);
+        let score_report = scenario.score(&entity_scores).unwrap();
+        insta::with_settings!({sort_maps => true}, {
+                insta::assert_yaml_snapshot!(score_report);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Metric \"metric-1\" score 11 is over its max-score 10")]
+    fn score_over_max_score_is_rejected() {
+        let scenario = parse_sdl(SDL).unwrap();
+        let entity_scores = EntityScores::from([(
+            "blue-team".to_string(),
+            MetricScores::from([("metric-1".to_string(), 11)]),
+        )]);
+        scenario.score(&entity_scores).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Entity \"red-team\" not found under Scenario Entities")]
+    fn unknown_entity_is_rejected() {
+        let scenario = parse_sdl(SDL).unwrap();
+        let entity_scores = EntityScores::from([("red-team".to_string(), MetricScores::new())]);
+        scenario.score(&entity_scores).unwrap();
+    }
+
+    #[test]
+    fn reports_unwinnable_evaluations() {
+        let sdl = r#"
+            name: test-scenario
+            nodes:
+                web:
+                    type: VM
+                    source: web-server
+                    resources:
+                        cpu: 1
+                        ram: 1 gib
+                    roles:
+                        admin: root
+                    conditions:
+                        web-check: admin
+            infrastructure:
+                web: 1
+            conditions:
+                web-check:
+                    command: /usr/bin/web-check.sh
+                    interval: 30
+                orphan-check:
+                    command: /usr/bin/orphan-check.sh
+                    interval: 30
+                web-or-orphan:
+                    any-of:
+                        - web-check
+                        - orphan-check
+            metrics:
+                metric-1:
+                    type: CONDITIONAL
+                    max-score: 10
+                    condition: web-check
+                metric-2:
+                    type: CONDITIONAL
+                    max-score: 10
+                    condition: orphan-check
+                metric-3:
+                    type: CONDITIONAL
+                    max-score: 10
+                    condition: web-or-orphan
+            evaluations:
+                evaluation-1:
+                    metrics:
+                        - metric-1
+                        - metric-3
+                    min-score: 100
+                evaluation-2:
+                    metrics:
+                        - metric-1
+                        - metric-2
+                    min-score: 60
+            tlos:
+                tlo-1:
+                    evaluation: evaluation-1
+                tlo-2:
+                    evaluation: evaluation-2
+            entities:
+                blue-team:
+                    role: Blue
+                    tlos:
+                        - tlo-1
+                        - tlo-2
+        "#;
+        let achievability_report = parse_sdl(sdl).unwrap().achievability_report().unwrap();
+        insta::with_settings!({sort_maps => true}, {
+                insta::assert_yaml_snapshot!(achievability_report);
+        });
+    }
+}
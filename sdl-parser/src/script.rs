@@ -3,7 +3,7 @@ use duration_str::parse;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 
-use crate::{event::Event, helpers::Connection, Formalize};
+use crate::{constants::default_speed_value, event::Event, helpers::Connection, Formalize};
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct Script {
@@ -23,8 +23,8 @@ pub struct Script {
         alias = "END-TIME"
     )]
     pub end_time: u64,
-    #[serde(alias = "Speed", alias = "SPEED")]
-    pub speed: f32,
+    #[serde(default = "default_speed_value", alias = "Speed", alias = "SPEED")]
+    pub speed: f64,
     #[serde(
         deserialize_with = "deserialize_events",
         alias = "Events",
@@ -57,7 +57,9 @@ impl Formalize for Script {
             }
         }
 
-        if self.speed.is_sign_negative() {
+        if !self.speed.is_finite() {
+            return Err(anyhow!("Scripts speed must be a finite number"));
+        } else if self.speed <= 0.0 {
             return Err(anyhow!("Scripts speed must have a positive value"));
         }
 
@@ -88,7 +90,7 @@ impl Connection<Event> for (&String, &Script) {
     }
 }
 
-fn parse_time_string_to_u64_sec(mut string: String) -> Result<u64> {
+pub(crate) fn parse_time_string_to_u64_sec(mut string: String) -> Result<u64> {
     if string.eq("0") {
         string = String::from("0sec");
     }
@@ -99,7 +101,7 @@ fn parse_time_string_to_u64_sec(mut string: String) -> Result<u64> {
     Ok(duration.as_secs())
 }
 
-fn deserialize_string_to_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+pub(crate) fn deserialize_string_to_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -109,6 +111,25 @@ where
     Ok(duration)
 }
 
+pub(crate) fn deserialize_optional_string_to_u32<'de, D>(
+    deserializer: D,
+) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(string) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    if let Ok(seconds) = string.trim().parse::<u32>() {
+        return Ok(Some(seconds));
+    }
+    let duration = parse_time_string_to_u64_sec(string)
+        .map_err(|_| serde::de::Error::custom("failed to parse str to duration"))?;
+    let duration =
+        u32::try_from(duration).map_err(|_| serde::de::Error::custom("duration is too long"))?;
+    Ok(Some(duration))
+}
+
 fn deserialize_events<'de, D>(deserializer: D) -> Result<HashMap<String, u64>, D::Error>
 where
     D: Deserializer<'de>,
@@ -241,6 +262,22 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[should_panic(expected = "Scripts speed must be a finite number")]
+    fn fails_on_infinite_speed_value() {
+        let script = r#"
+            start-time: 0
+            end-time: 3 hour
+            speed: .inf
+            events:
+                my-cool-event: 2 hour
+      "#;
+        serde_yaml::from_str::<Script>(script)
+            .unwrap()
+            .formalize()
+            .unwrap();
+    }
+
     #[test]
     #[should_panic(
         expected = "Condition must have Command and Interval or Source defined, not both"
@@ -0,0 +1,845 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{
+    condition::{Condition, ConditionEvaluation},
+    entity::Flatten,
+    event::{Event, TriggerType},
+    inject::{ExpectedResponse, ResponseType},
+    metric::MetricType,
+    schedule::Schedule,
+    scoring::{EntityScores, MetricScores, ScoreReport},
+    script::deserialize_string_to_u64,
+    script::Script,
+    story::Story,
+    timeline::{script_offset, wall_offset},
+    Scenario,
+};
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct ConditionChange {
+    #[serde(alias = "Condition", alias = "CONDITION")]
+    pub condition: String,
+    #[serde(
+        deserialize_with = "deserialize_string_to_u64",
+        alias = "At",
+        alias = "AT"
+    )]
+    pub at: u64,
+    #[serde(default = "default_condition_value", alias = "Value", alias = "VALUE")]
+    pub value: bool,
+}
+
+fn default_condition_value() -> bool {
+    true
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SimulationAction {
+    ConditionChanged {
+        condition: String,
+        value: bool,
+    },
+    EventTriggered {
+        story: String,
+        script: String,
+        event: String,
+    },
+    EventPending {
+        story: String,
+        script: String,
+        event: String,
+        unmet_conditions: Vec<String>,
+    },
+    EventSkipped {
+        story: String,
+        script: String,
+        event: String,
+        unmet_conditions: Vec<String>,
+    },
+    InjectDelivered {
+        event: String,
+        inject: String,
+        from_entity: Option<String>,
+        to_entities: Vec<String>,
+    },
+    ResponseDeadline {
+        inject: String,
+        response: String,
+        to_entities: Vec<String>,
+        met: Option<bool>,
+    },
+    EvaluationChanged {
+        entity: String,
+        evaluation: String,
+        score: u32,
+        max_score: u32,
+        passed: bool,
+    },
+    TloChanged {
+        entity: String,
+        tlo: String,
+        achieved: bool,
+    },
+    GoalChanged {
+        entity: String,
+        goal: String,
+        completed: bool,
+    },
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SimulationLogEntry {
+    pub time: f64,
+    pub timestamp: Option<DateTime<FixedOffset>>,
+    pub action: SimulationAction,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Simulation {
+    pub log: Vec<SimulationLogEntry>,
+    pub scores: ScoreReport,
+}
+
+#[derive(Clone, Copy)]
+struct ScriptClock<'a> {
+    story_name: &'a String,
+    story: &'a Story,
+    script_name: &'a String,
+    script: &'a Script,
+    schedule: Option<&'a Schedule>,
+}
+
+impl ScriptClock<'_> {
+    fn wall_offset(&self, script_offset: f64) -> f64 {
+        wall_offset(self.story, self.script, script_offset)
+    }
+
+    fn script_offset(&self, wall_offset: f64) -> f64 {
+        script_offset(self.story, self.script, wall_offset)
+    }
+}
+
+enum SimulationStep<'a> {
+    ConditionChange(&'a ConditionChange),
+    Arm(ScriptClock<'a>, &'a String, f64),
+    Fire(ScriptClock<'a>, &'a String, f64),
+    ResponseDeadline(
+        Option<&'a Schedule>,
+        &'a String,
+        &'a String,
+        &'a ExpectedResponse,
+    ),
+}
+
+struct QueuedStep<'a> {
+    time: f64,
+    priority: u8,
+    order: usize,
+    step: SimulationStep<'a>,
+}
+
+impl QueuedStep<'_> {
+    fn key(&self) -> (f64, u8, usize) {
+        (self.time, self.priority, self.order)
+    }
+}
+
+impl PartialEq for QueuedStep<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedStep<'_> {}
+
+impl PartialOrd for QueuedStep<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedStep<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ((time, priority, order), (other_time, other_priority, other_order)) =
+            (self.key(), other.key());
+        other_time
+            .total_cmp(&time)
+            .then(other_priority.cmp(&priority))
+            .then(other_order.cmp(&order))
+    }
+}
+
+#[derive(Default)]
+struct SimulationQueue<'a> {
+    steps: BinaryHeap<QueuedStep<'a>>,
+    next_order: usize,
+}
+
+impl<'a> SimulationQueue<'a> {
+    fn push(&mut self, time: f64, step: SimulationStep<'a>) {
+        let priority = match step {
+            SimulationStep::ConditionChange(_) => 0,
+            _ => 1,
+        };
+        self.steps.push(QueuedStep {
+            time,
+            priority,
+            order: self.next_order,
+            step,
+        });
+        self.next_order += 1;
+    }
+
+    fn push_condition_change(&mut self, change: &'a ConditionChange) {
+        self.push(change.at as f64, SimulationStep::ConditionChange(change));
+    }
+
+    fn push_event_step(&mut self, step: SimulationStep<'a>) {
+        let (SimulationStep::Arm(clock, _, script_offset)
+        | SimulationStep::Fire(clock, _, script_offset)) = &step
+        else {
+            return;
+        };
+        if *script_offset > clock.script.end_time as f64 {
+            return;
+        }
+        self.push(clock.wall_offset(*script_offset), step);
+    }
+
+    fn pop(&mut self) -> Option<(f64, SimulationStep<'a>)> {
+        self.steps
+            .pop()
+            .map(|queued_step| (queued_step.time, queued_step.step))
+    }
+}
+
+impl Scenario {
+    pub fn simulate(&self, condition_changes: &[ConditionChange]) -> Result<Simulation> {
+        self.validate_condition_changes(condition_changes)?;
+
+        let mut queue = SimulationQueue::default();
+        for change in condition_changes {
+            queue.push_condition_change(change);
+        }
+        if let Some(stories) = &self.stories {
+            let mut story_names = stories.keys().collect::<Vec<&String>>();
+            story_names.sort();
+            for story_name in story_names {
+                let story = &stories[story_name];
+                for script_name in story.scripts.iter() {
+                    let script = self
+                        .scripts
+                        .as_ref()
+                        .and_then(|scripts| scripts.get(script_name))
+                        .ok_or_else(|| {
+                            anyhow!("Script \"{script_name}\" not found under Scenario")
+                        })?;
+                    let clock = ScriptClock {
+                        story_name,
+                        story,
+                        script_name,
+                        script,
+                        schedule: story.schedule.as_ref().or(self.schedule.as_ref()),
+                    };
+                    let mut scheduled_events = script.events.iter().collect::<Vec<_>>();
+                    scheduled_events.sort();
+                    for (event_name, script_offset) in scheduled_events {
+                        queue.push_event_step(SimulationStep::Arm(
+                            clock,
+                            event_name,
+                            *script_offset as f64,
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut condition_states = HashMap::<String, bool>::new();
+        let mut pending_events = Vec::<(ScriptClock, &String)>::new();
+        let mut simulation = Simulation {
+            log: vec![],
+            scores: self.score(&self.simulated_entity_scores(&condition_states))?,
+        };
+        while let Some((time, step)) = queue.pop() {
+            match step {
+                SimulationStep::ConditionChange(change) => {
+                    condition_states.insert(change.condition.to_owned(), change.value);
+                    simulation.push(
+                        self.schedule.as_ref(),
+                        time,
+                        SimulationAction::ConditionChanged {
+                            condition: change.condition.to_owned(),
+                            value: change.value,
+                        },
+                    );
+
+                    let scores = self.score(&self.simulated_entity_scores(&condition_states))?;
+                    for action in score_changes(&simulation.scores, &scores) {
+                        simulation.push(self.schedule.as_ref(), time, action);
+                    }
+                    simulation.scores = scores;
+
+                    let mut still_pending = vec![];
+                    for (clock, event_name) in pending_events {
+                        let event = self.simulated_event(event_name)?;
+                        if event.are_conditions_met(|condition_name| {
+                            self.is_condition_true(condition_name, &condition_states)
+                        }) {
+                            queue.push_event_step(SimulationStep::Fire(
+                                clock,
+                                event_name,
+                                clock.script_offset(time) + event.trigger_delay() as f64,
+                            ));
+                        } else {
+                            still_pending.push((clock, event_name));
+                        }
+                    }
+                    pending_events = still_pending;
+                }
+                SimulationStep::Arm(clock, event_name, script_offset) => {
+                    let event = self.simulated_event(event_name)?;
+                    let mut unmet_conditions = event
+                        .conditions
+                        .iter()
+                        .flatten()
+                        .filter(|condition_name| {
+                            !self.is_condition_true(condition_name, &condition_states)
+                        })
+                        .cloned()
+                        .collect::<Vec<String>>();
+                    unmet_conditions.sort();
+                    let conditions_met = event.are_conditions_met(|condition_name| {
+                        self.is_condition_true(condition_name, &condition_states)
+                    });
+
+                    match event.trigger_type() {
+                        TriggerType::Conditions if conditions_met => {
+                            queue.push_event_step(SimulationStep::Fire(
+                                clock,
+                                event_name,
+                                script_offset + event.trigger_delay() as f64,
+                            ));
+                        }
+                        TriggerType::Conditions => {
+                            simulation.push(
+                                clock.schedule,
+                                time,
+                                SimulationAction::EventPending {
+                                    story: clock.story_name.to_owned(),
+                                    script: clock.script_name.to_owned(),
+                                    event: event_name.to_owned(),
+                                    unmet_conditions,
+                                },
+                            );
+                            pending_events.push((clock, event_name));
+                        }
+                        _ => {
+                            if conditions_met {
+                                self.fire_event(
+                                    &mut simulation,
+                                    &mut queue,
+                                    time,
+                                    (clock, event_name, script_offset),
+                                )?;
+                            } else {
+                                simulation.push(
+                                    clock.schedule,
+                                    time,
+                                    SimulationAction::EventSkipped {
+                                        story: clock.story_name.to_owned(),
+                                        script: clock.script_name.to_owned(),
+                                        event: event_name.to_owned(),
+                                        unmet_conditions,
+                                    },
+                                );
+                            }
+                            if let Some(repeat) = event.trigger_repeat() {
+                                queue.push_event_step(SimulationStep::Arm(
+                                    clock,
+                                    event_name,
+                                    script_offset + repeat as f64,
+                                ));
+                            }
+                        }
+                    }
+                }
+                SimulationStep::ResponseDeadline(
+                    schedule,
+                    inject_name,
+                    response_name,
+                    response,
+                ) => {
+                    let met = match response.response_type {
+                        ResponseType::Automated => {
+                            response.condition.as_ref().map(|condition_name| {
+                                self.is_condition_true(condition_name, &condition_states)
+                            })
+                        }
+                        ResponseType::Manual => None,
+                    };
+                    simulation.push(
+                        schedule,
+                        time,
+                        SimulationAction::ResponseDeadline {
+                            inject: inject_name.to_owned(),
+                            response: response_name.to_owned(),
+                            to_entities: self
+                                .injects
+                                .as_ref()
+                                .and_then(|injects| injects.get(inject_name))
+                                .and_then(|inject| inject.to_entities.to_owned())
+                                .unwrap_or_default(),
+                            met,
+                        },
+                    );
+                }
+                SimulationStep::Fire(clock, event_name, script_offset) => {
+                    self.fire_event(
+                        &mut simulation,
+                        &mut queue,
+                        time,
+                        (clock, event_name, script_offset),
+                    )?;
+                    let event = self.simulated_event(event_name)?;
+                    if let Some(repeat) = event.trigger_repeat() {
+                        queue.push_event_step(SimulationStep::Arm(
+                            clock,
+                            event_name,
+                            script_offset + repeat as f64,
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(simulation)
+    }
+
+    fn simulated_event(&self, event_name: &str) -> Result<&Event> {
+        self.events
+            .as_ref()
+            .and_then(|events| events.get(event_name))
+            .ok_or_else(|| anyhow!("Event \"{event_name}\" not found under Scenario Events"))
+    }
+
+    fn fire_event<'a>(
+        &'a self,
+        simulation: &mut Simulation,
+        queue: &mut SimulationQueue<'a>,
+        time: f64,
+        (clock, event_name, script_offset): (ScriptClock<'a>, &'a String, f64),
+    ) -> Result<()> {
+        let event = self.simulated_event(event_name)?;
+        simulation.push(
+            clock.schedule,
+            time,
+            SimulationAction::EventTriggered {
+                story: clock.story_name.to_owned(),
+                script: clock.script_name.to_owned(),
+                event: event_name.to_owned(),
+            },
+        );
+        for inject_name in event.injects.iter().flatten() {
+            let (inject_name, inject) = self
+                .injects
+                .as_ref()
+                .and_then(|injects| injects.get_key_value(inject_name))
+                .ok_or_else(|| {
+                    anyhow!("Inject \"{inject_name}\" not found under Scenario Injects")
+                })?;
+            simulation.push(
+                clock.schedule,
+                time,
+                SimulationAction::InjectDelivered {
+                    event: event_name.to_owned(),
+                    inject: inject_name.to_owned(),
+                    from_entity: inject.from_entity.to_owned(),
+                    to_entities: inject.to_entities.to_owned().unwrap_or_default(),
+                },
+            );
+            let mut responses = inject
+                .expected_responses
+                .iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            responses.sort_by(|first, second| first.0.cmp(second.0));
+            for (response_name, response) in responses {
+                queue.push(
+                    time + response.deadline as f64,
+                    SimulationStep::ResponseDeadline(
+                        clock.schedule,
+                        inject_name,
+                        response_name,
+                        response,
+                    ),
+                );
+            }
+        }
+
+        let mut followers = self
+            .events
+            .iter()
+            .flatten()
+            .filter(|(_, follower)| follower.triggered_after() == Some(event_name))
+            .collect::<Vec<_>>();
+        followers.sort_by(|first, second| first.0.cmp(second.0));
+        for (follower_name, follower) in followers {
+            queue.push_event_step(SimulationStep::Arm(
+                clock,
+                follower_name,
+                script_offset + follower.trigger_delay() as f64,
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_condition_changes(&self, condition_changes: &[ConditionChange]) -> Result<()> {
+        for change in condition_changes {
+            let condition = self
+                .conditions
+                .as_ref()
+                .and_then(|conditions| conditions.get(&change.condition))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Condition \"{}\" not found under Scenario Conditions",
+                        change.condition
+                    )
+                })?;
+            if condition.is_composite() {
+                return Err(anyhow!(
+                    "Composite Condition \"{}\" can not be changed directly in a simulation",
+                    change.condition
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn is_condition_true(&self, condition_name: &str, states: &HashMap<String, bool>) -> bool {
+        Condition::evaluate(
+            condition_name,
+            &self.conditions,
+            ConditionEvaluation::Truth,
+            &|condition_name| states.get(condition_name).copied().unwrap_or_default(),
+        )
+    }
+
+    fn simulated_entity_scores(&self, states: &HashMap<String, bool>) -> EntityScores {
+        let metric_scores =
+            self.metrics
+                .iter()
+                .flatten()
+                .filter(|(_, metric)| metric.metric_type == MetricType::Conditional)
+                .filter(|(_, metric)| {
+                    metric.condition.as_ref().is_some_and(|condition_name| {
+                        self.is_condition_true(condition_name, states)
+                    })
+                })
+                .map(|(metric_name, metric)| (metric_name.to_owned(), metric.max_score))
+                .collect::<MetricScores>();
+
+        self.entities
+            .as_ref()
+            .map(|entities| entities.flatten())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, entity)| entity.tlos.is_some())
+            .map(|(entity_name, _)| (entity_name, metric_scores.clone()))
+            .collect()
+    }
+}
+
+impl Simulation {
+    fn push(&mut self, schedule: Option<&Schedule>, time: f64, action: SimulationAction) {
+        self.log.push(SimulationLogEntry {
+            time,
+            timestamp: schedule.map(|schedule| schedule.resolve(time)),
+            action,
+        });
+    }
+}
+
+fn sorted_keys<V>(map: &HashMap<String, V>) -> Vec<&String> {
+    let mut keys = map.keys().collect::<Vec<&String>>();
+    keys.sort();
+    keys
+}
+
+fn score_changes(previous: &ScoreReport, current: &ScoreReport) -> Vec<SimulationAction> {
+    let mut actions = vec![];
+    for entity_name in sorted_keys(current) {
+        let report = &current[entity_name];
+        let previous_report = previous.get(entity_name);
+
+        for evaluation_name in sorted_keys(&report.evaluations) {
+            let result = &report.evaluations[evaluation_name];
+            let previous_result =
+                previous_report.and_then(|previous| previous.evaluations.get(evaluation_name));
+            if previous_result.is_none_or(|previous| {
+                previous.score != result.score || previous.passed != result.passed
+            }) {
+                actions.push(SimulationAction::EvaluationChanged {
+                    entity: entity_name.to_owned(),
+                    evaluation: evaluation_name.to_owned(),
+                    score: result.score,
+                    max_score: result.max_score,
+                    passed: result.passed,
+                });
+            }
+        }
+        for tlo_name in sorted_keys(&report.tlos) {
+            let achieved = report.tlos[tlo_name].achieved;
+            if previous_report
+                .and_then(|previous| previous.tlos.get(tlo_name))
+                .is_none_or(|previous| previous.achieved != achieved)
+            {
+                actions.push(SimulationAction::TloChanged {
+                    entity: entity_name.to_owned(),
+                    tlo: tlo_name.to_owned(),
+                    achieved,
+                });
+            }
+        }
+        for goal_name in sorted_keys(&report.goals) {
+            let completed = report.goals[goal_name].completed;
+            if previous_report
+                .and_then(|previous| previous.goals.get(goal_name))
+                .is_none_or(|previous| previous.completed != completed)
+            {
+                actions.push(SimulationAction::GoalChanged {
+                    entity: entity_name.to_owned(),
+                    goal: goal_name.to_owned(),
+                    completed,
+                });
+            }
+        }
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        schedule:
+            start: 2024-05-06T09:00:00Z
+        conditions:
+            mail-up:
+                command: executable/path.sh
+                interval: 30
+            phish-reported:
+                command: executable/path.sh
+                interval: 30
+        metrics:
+            metric-1:
+                type: CONDITIONAL
+                max-score: 10
+                condition: phish-reported
+        evaluations:
+            evaluation-1:
+                metrics:
+                    - metric-1
+                min-score: 50
+        tlos:
+            tlo-1:
+                evaluation: evaluation-1
+        goals:
+            goal-1:
+                tlos:
+                    - tlo-1
+        entities:
+            white-team:
+                role: White
+            blue-team:
+                role: Blue
+                tlos:
+                    - tlo-1
+        injects:
+            phishing-email:
+                source: inject-package
+                from-entity: white-team
+                to-entities:
+                    - blue-team
+        events:
+            phishing-wave:
+                conditions:
+                    - mail-up
+                injects:
+                    - phishing-email
+            second-wave:
+                conditions:
+                    - mail-up
+                injects:
+                    - phishing-email
+        scripts:
+            main-script:
+                start-time: 0
+                end-time: 2h
+                speed: 1
+                events:
+                    phishing-wave: 10min
+                    second-wave: 30min
+        stories:
+            main-story:
+                scripts:
+                    - main-script
+    "#;
+
+    const CONDITION_CHANGES: &str = r#"
+        - condition: mail-up
+          at: 20min
+        - condition: phish-reported
+          at: 45min
+        - condition: mail-up
+          at: 1h
+          value: false
+    "#;
+
+    #[test]
+    fn simulates_events_injects_and_scores() {
+        let condition_changes =
+            serde_yaml::from_str::<Vec<ConditionChange>>(CONDITION_CHANGES).unwrap();
+        let simulation = parse_sdl(SDL)
+            .unwrap()
+            .simulate(&condition_changes)
+            .unwrap();
+        insta::with_settings!({sort_maps => true}, {
+            insta::assert_yaml_snapshot!(simulation);
+        });
+    }
+
+    #[test]
+    fn simulation_follows_event_triggers() {
+        let sdl = SDL.replace(
+            "                conditions:\n                    - mail-up\n                injects:\n                    - phishing-email\n            second-wave:\n                conditions:\n                    - mail-up\n                injects:\n                    - phishing-email\n",
+            "                conditions:\n                    - mail-up\n                injects:\n                    - phishing-email\n                trigger:\n                    type: conditions\n                    delay: 5min\n            follow-up:\n                injects:\n                    - phishing-email\n                trigger:\n                    type: after\n                    event: phishing-wave\n                    delay: 10min\n                    repeat: 30min\n",
+        )
+        .replace("                    second-wave: 30min\n", "");
+        let condition_changes =
+            serde_yaml::from_str::<Vec<ConditionChange>>(CONDITION_CHANGES).unwrap();
+        let simulation = parse_sdl(&sdl)
+            .unwrap()
+            .simulate(&condition_changes)
+            .unwrap();
+        let log = simulation
+            .log
+            .into_iter()
+            .filter(|entry| {
+                matches!(
+                    entry.action,
+                    SimulationAction::EventPending { .. } | SimulationAction::EventTriggered { .. }
+                )
+            })
+            .collect::<Vec<SimulationLogEntry>>();
+        insta::assert_yaml_snapshot!(log);
+    }
+
+    #[test]
+    fn simulation_checks_conditions_of_followers_and_repeats() {
+        let sdl = SDL.replace(
+            "                conditions:\n                    - mail-up\n                injects:\n                    - phishing-email\n            second-wave:\n                conditions:\n                    - mail-up\n                injects:\n                    - phishing-email\n",
+            "                conditions:\n                    - mail-up\n                injects:\n                    - phishing-email\n                trigger:\n                    type: conditions\n                    delay: 5min\n            follow-up:\n                conditions:\n                    - phish-reported\n                trigger:\n                    type: after\n                    event: phishing-wave\n                    delay: 10min\n                    repeat: 30min\n",
+        )
+        .replace("                    second-wave: 30min\n", "");
+        let condition_changes =
+            serde_yaml::from_str::<Vec<ConditionChange>>(CONDITION_CHANGES).unwrap();
+        let follow_ups = parse_sdl(&sdl)
+            .unwrap()
+            .simulate(&condition_changes)
+            .unwrap()
+            .log
+            .into_iter()
+            .filter_map(|entry| match entry.action {
+                SimulationAction::EventTriggered { event, .. } if event == "follow-up" => {
+                    Some((entry.time, true))
+                }
+                SimulationAction::EventSkipped { event, .. } if event == "follow-up" => {
+                    Some((entry.time, false))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            follow_ups,
+            vec![(2100.0, false), (3900.0, true), (5700.0, true)]
+        );
+    }
+
+    #[test]
+    fn simulation_resolves_timestamps_in_story_schedule() {
+        let sdl = SDL
+            .replace(
+                "        schedule:\n            start: 2024-05-06T09:00:00Z\n",
+                "",
+            )
+            .replace(
+                "                scripts:\n                    - main-script\n",
+                "                scripts:\n                    - main-script\n                schedule:\n                    start: 2024-05-07T09:00:00Z\n",
+            );
+        let condition_changes =
+            serde_yaml::from_str::<Vec<ConditionChange>>(CONDITION_CHANGES).unwrap();
+        let log = parse_sdl(&sdl)
+            .unwrap()
+            .simulate(&condition_changes)
+            .unwrap()
+            .log;
+        let triggered = log
+            .iter()
+            .filter(|entry| matches!(entry.action, SimulationAction::EventTriggered { .. }))
+            .map(|entry| entry.timestamp.map(|timestamp| timestamp.to_rfc3339()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            triggered,
+            vec![Some("2024-05-07T09:30:00+00:00".to_string())]
+        );
+        assert!(log
+            .iter()
+            .filter(|entry| matches!(entry.action, SimulationAction::ConditionChanged { .. }))
+            .all(|entry| entry.timestamp.is_none()));
+    }
+
+    #[test]
+    fn simulation_checks_response_deadlines() {
+        let sdl = SDL.replace(
+            "                to-entities:\n                    - blue-team\n",
+            "                to-entities:\n                    - blue-team\n                expected-responses:\n                    report-phish:\n                        type: automated\n                        deadline: 20min\n                        metric: metric-1\n                        condition: phish-reported\n",
+        );
+        let condition_changes =
+            serde_yaml::from_str::<Vec<ConditionChange>>(CONDITION_CHANGES).unwrap();
+        let deadlines = parse_sdl(&sdl)
+            .unwrap()
+            .simulate(&condition_changes)
+            .unwrap()
+            .log
+            .into_iter()
+            .filter_map(|entry| match entry.action {
+                SimulationAction::ResponseDeadline { met, .. } => Some((entry.time, met)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(deadlines, vec![(3000.0, Some(true))]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Condition \"missing\" not found under Scenario Conditions")]
+    fn unknown_condition_change_is_rejected() {
+        let condition_changes = vec![ConditionChange {
+            condition: "missing".to_string(),
+            at: 0,
+            value: true,
+        }];
+        parse_sdl(SDL)
+            .unwrap()
+            .simulate(&condition_changes)
+            .unwrap();
+    }
+}
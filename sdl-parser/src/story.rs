@@ -1,6 +1,10 @@
 use crate::helpers::Connection;
 use crate::Formalize;
-use crate::{constants::default_speed_value, script::Script};
+use crate::{
+    constants::{default_speed_value, MINIMUM_STORY_SPEED},
+    schedule::Schedule,
+    script::Script,
+};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -15,6 +19,8 @@ pub struct Story {
     pub scripts: Vec<String>,
     #[serde(alias = "Description", alias = "DESCRIPTION")]
     pub description: Option<String>,
+    #[serde(default, alias = "Schedule", alias = "SCHEDULE")]
+    pub schedule: Option<Schedule>,
 }
 
 impl Story {
@@ -27,6 +33,12 @@ impl Story {
             ..Default::default()
         }
     }
+
+    /// Script time runs `story.speed * script.speed` times faster than wall-clock time.
+    /// Script start times are only scaled by the story speed.
+    pub fn effective_speed(&self, script: &Script) -> f64 {
+        self.speed * script.speed
+    }
 }
 
 pub type Stories = HashMap<String, Story>;
@@ -37,8 +49,16 @@ impl Formalize for Story {
             return Err(anyhow!("Story must have have at least one Script"));
         }
 
-        if self.speed < 1.0 {
-            return Err(anyhow!("Story speed value must be at least 1.0"));
+        if !self.speed.is_finite() {
+            return Err(anyhow!("Story speed value must be a finite number"));
+        } else if self.speed < MINIMUM_STORY_SPEED {
+            return Err(anyhow!(
+                "Story speed value must be at least {MINIMUM_STORY_SPEED:.1}"
+            ));
+        }
+
+        if let Some(schedule) = &mut self.schedule {
+            schedule.formalize()?;
         }
 
         Ok(())
@@ -136,6 +156,20 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[should_panic(expected = "Story speed value must be a finite number")]
+    fn fails_speed_is_not_a_number() {
+        let story = r#"
+            speed: .nan
+            scripts:
+                - script-1
+      "#;
+        serde_yaml::from_str::<Story>(story)
+            .unwrap()
+            .formalize()
+            .unwrap();
+    }
+
     #[test]
     fn adds_default_speed() {
         let story = r#"
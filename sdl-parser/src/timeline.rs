@@ -0,0 +1,251 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::{script::Script, story::Story, Scenario};
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct TimelineEntry {
+    pub story: String,
+    pub script: String,
+    pub event: String,
+    pub script_offset: u64,
+    pub wall_offset: f64,
+    pub timestamp: Option<DateTime<FixedOffset>>,
+    pub injects: Vec<String>,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct ScriptWindow {
+    pub story: String,
+    pub script: String,
+    pub wall_start: f64,
+    pub wall_end: f64,
+    pub start_timestamp: Option<DateTime<FixedOffset>>,
+    pub end_timestamp: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct ScriptOverlap {
+    pub first: ScriptWindow,
+    pub second: ScriptWindow,
+    pub wall_start: f64,
+    pub wall_end: f64,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Timeline {
+    pub entries: Vec<TimelineEntry>,
+    pub scripts: Vec<ScriptWindow>,
+    pub overlaps: Vec<ScriptOverlap>,
+}
+
+pub(crate) fn wall_offset(story: &Story, script: &Script, script_offset: f64) -> f64 {
+    let start_time = script.start_time as f64;
+    let elapsed = (script_offset - start_time).max(0.0);
+    start_time / story.speed + elapsed / story.effective_speed(script)
+}
+
+pub(crate) fn script_offset(story: &Story, script: &Script, wall_offset: f64) -> f64 {
+    let start_time = script.start_time as f64;
+    let elapsed = (wall_offset - start_time / story.speed).max(0.0);
+    start_time + elapsed * story.effective_speed(script)
+}
+
+impl Scenario {
+    pub fn timeline(&self) -> Result<Timeline> {
+        let mut timeline = Timeline::default();
+        let Some(stories) = &self.stories else {
+            return Ok(timeline);
+        };
+
+        let mut story_names = stories.keys().collect::<Vec<&String>>();
+        story_names.sort();
+        for story_name in story_names {
+            let story = &stories[story_name];
+            let schedule = story.schedule.as_ref().or(self.schedule.as_ref());
+            for script_name in story.scripts.iter() {
+                let script = self
+                    .scripts
+                    .as_ref()
+                    .and_then(|scripts| scripts.get(script_name))
+                    .ok_or_else(|| anyhow!("Script \"{script_name}\" not found under Scenario"))?;
+
+                let wall_start = wall_offset(story, script, script.start_time as f64);
+                let wall_end = wall_offset(story, script, script.end_time as f64);
+                timeline.scripts.push(ScriptWindow {
+                    story: story_name.to_owned(),
+                    script: script_name.to_owned(),
+                    wall_start,
+                    wall_end,
+                    start_timestamp: schedule.map(|schedule| schedule.resolve(wall_start)),
+                    end_timestamp: schedule.map(|schedule| schedule.resolve(wall_end)),
+                });
+                for (event_name, script_offset) in script.events.iter() {
+                    let delay = self
+                        .events
+                        .as_ref()
+                        .and_then(|events| events.get(event_name))
+                        .map(|event| event.trigger_delay())
+                        .unwrap_or_default();
+                    self.push_event_occurrences(
+                        &mut timeline,
+                        (story_name, story),
+                        (script_name, script),
+                        event_name,
+                        script_offset + delay as u64,
+                    );
+                }
+            }
+        }
+
+        timeline.entries.sort_by(|first, second| {
+            first
+                .wall_offset
+                .total_cmp(&second.wall_offset)
+                .then_with(|| first.story.cmp(&second.story))
+                .then_with(|| first.script.cmp(&second.script))
+                .then_with(|| first.event.cmp(&second.event))
+        });
+        for (index, first) in timeline.scripts.iter().enumerate() {
+            for second in timeline.scripts.iter().skip(index + 1) {
+                let wall_start = first.wall_start.max(second.wall_start);
+                let wall_end = first.wall_end.min(second.wall_end);
+                if wall_start < wall_end {
+                    timeline.overlaps.push(ScriptOverlap {
+                        first: first.to_owned(),
+                        second: second.to_owned(),
+                        wall_start,
+                        wall_end,
+                    });
+                }
+            }
+        }
+        Ok(timeline)
+    }
+
+    fn push_event_occurrences(
+        &self,
+        timeline: &mut Timeline,
+        (story_name, story): (&String, &Story),
+        (script_name, script): (&String, &Script),
+        event_name: &String,
+        first_offset: u64,
+    ) {
+        let event = self
+            .events
+            .as_ref()
+            .and_then(|events| events.get(event_name));
+        let schedule = story.schedule.as_ref().or(self.schedule.as_ref());
+        let mut followers = self
+            .events
+            .iter()
+            .flatten()
+            .filter(|(_, follower)| follower.triggered_after() == Some(event_name))
+            .collect::<Vec<_>>();
+        followers.sort_by(|first, second| first.0.cmp(second.0));
+
+        let mut script_offset = first_offset;
+        while script_offset <= script.end_time {
+            let event_wall_offset = wall_offset(story, script, script_offset as f64);
+            timeline.entries.push(TimelineEntry {
+                story: story_name.to_owned(),
+                script: script_name.to_owned(),
+                event: event_name.to_owned(),
+                script_offset,
+                wall_offset: event_wall_offset,
+                timestamp: schedule.map(|schedule| schedule.resolve(event_wall_offset)),
+                injects: event
+                    .and_then(|event| event.injects.to_owned())
+                    .unwrap_or_default(),
+            });
+            for (follower_name, follower) in followers.iter() {
+                self.push_event_occurrences(
+                    timeline,
+                    (story_name, story),
+                    (script_name, script),
+                    follower_name,
+                    script_offset + follower.trigger_delay() as u64,
+                );
+            }
+
+            match event.and_then(|event| event.trigger_repeat()) {
+                Some(repeat) => script_offset += repeat as u64,
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        conditions:
+            condition-1:
+                command: executable/path.sh
+                interval: 30
+        injects:
+            phishing-email:
+                source: inject-package
+            ransom-note:
+                source: inject-package
+        events:
+            phishing-wave:
+                conditions:
+                    - condition-1
+                injects:
+                    - phishing-email
+            ransomware:
+                conditions:
+                    - condition-1
+                injects:
+                    - ransom-note
+        scripts:
+            morning:
+                start-time: 0
+                end-time: 4h
+                speed: 1
+                events:
+                    phishing-wave: 1h
+            afternoon:
+                start-time: 2h
+                end-time: 6h
+                speed: 2
+                events:
+                    ransomware: 4h
+        stories:
+            day-one:
+                speed: 2
+                scripts:
+                    - morning
+                    - afternoon
+    "#;
+
+    #[test]
+    fn timeline_applies_story_and_script_speeds() {
+        let timeline = parse_sdl(SDL).unwrap().timeline().unwrap();
+        insta::assert_yaml_snapshot!(timeline);
+    }
+
+    #[test]
+    #[should_panic(expected = "Scripts speed must have a positive value")]
+    fn zero_script_speed_is_rejected() {
+        let sdl = SDL.replace(
+            "speed: 2\n                events",
+            "speed: 0\n                events",
+        );
+        parse_sdl(&sdl).unwrap();
+    }
+
+    #[test]
+    fn script_speed_defaults_to_one() {
+        let scenario = parse_sdl(&SDL.replace("                speed: 1\n", "")).unwrap();
+        let story = &scenario.stories.as_ref().unwrap()["day-one"];
+        let scripts = scenario.scripts.as_ref().unwrap();
+        assert_eq!(story.effective_speed(&scripts["morning"]), 2.0);
+        assert_eq!(story.effective_speed(&scripts["afternoon"]), 4.0);
+    }
+}
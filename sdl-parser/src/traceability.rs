@@ -0,0 +1,298 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    condition::Condition,
+    entity::Flatten,
+    helpers::{escape_csv_field, escape_markdown_cell},
+    node::NodeType,
+    Scenario,
+};
+
+const TRACEABILITY_COLUMNS: [&str; 7] = [
+    "Goal",
+    "TLO",
+    "Evaluation",
+    "Metric",
+    "Condition",
+    "VMs",
+    "Entities",
+];
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct MetricTrace {
+    pub metric: String,
+    pub condition: Option<String>,
+    pub vms: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct TloTrace {
+    pub tlo: String,
+    pub evaluation: String,
+    pub metrics: Vec<MetricTrace>,
+    pub entities: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct GoalTrace {
+    pub goal: String,
+    pub tlos: Vec<TloTrace>,
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Traceability {
+    pub goals: Vec<GoalTrace>,
+}
+
+impl Traceability {
+    pub fn rows(&self) -> Vec<[String; 7]> {
+        let mut rows = vec![];
+        for goal_trace in self.goals.iter() {
+            for tlo_trace in goal_trace.tlos.iter() {
+                for metric_trace in tlo_trace.metrics.iter() {
+                    rows.push([
+                        goal_trace.goal.to_owned(),
+                        tlo_trace.tlo.to_owned(),
+                        tlo_trace.evaluation.to_owned(),
+                        metric_trace.metric.to_owned(),
+                        metric_trace.condition.to_owned().unwrap_or_default(),
+                        metric_trace.vms.join(", "),
+                        tlo_trace.entities.join(", "),
+                    ]);
+                }
+            }
+        }
+        rows
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\r\n", TRACEABILITY_COLUMNS.join(","));
+        for row in self.rows() {
+            let fields = row
+                .iter()
+                .map(|field| escape_csv_field(field))
+                .collect::<Vec<String>>();
+            csv.push_str(&format!("{}\r\n", fields.join(",")));
+        }
+        csv
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("| {} |\n", TRACEABILITY_COLUMNS.join(" | "));
+        markdown.push_str(&format!(
+            "|{}\n",
+            TRACEABILITY_COLUMNS.map(|_| " --- |").concat()
+        ));
+        for row in self.rows() {
+            let cells = row
+                .iter()
+                .map(|cell| escape_markdown_cell(cell))
+                .collect::<Vec<String>>();
+            markdown.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        markdown
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self)
+            .map_err(|e| anyhow!("Failed to serialize to json: {}", e))
+    }
+}
+
+impl Scenario {
+    pub fn traceability(&self) -> Traceability {
+        let entities = self
+            .entities
+            .as_ref()
+            .map(|entities| entities.flatten())
+            .unwrap_or_default();
+
+        let mut goal_names = self
+            .goals
+            .as_ref()
+            .map(|goals| goals.keys().cloned().collect::<Vec<String>>())
+            .unwrap_or_default();
+        goal_names.sort();
+
+        let mut traceability = Traceability::default();
+        for goal_name in goal_names {
+            let goal = &self.goals.as_ref().unwrap()[&goal_name];
+            let mut tlo_traces = vec![];
+            for tlo_name in goal.tlos.iter() {
+                let Some(tlo) = self.tlos.as_ref().and_then(|tlos| tlos.get(tlo_name)) else {
+                    continue;
+                };
+                let metric_names = self
+                    .evaluations
+                    .as_ref()
+                    .and_then(|evaluations| evaluations.get(&tlo.evaluation))
+                    .map(|evaluation| evaluation.metrics.to_owned())
+                    .unwrap_or_default();
+                let metric_traces = metric_names
+                    .into_iter()
+                    .map(|metric_name| {
+                        let condition = self
+                            .metrics
+                            .as_ref()
+                            .and_then(|metrics| metrics.get(&metric_name))
+                            .and_then(|metric| metric.condition.to_owned());
+                        let vms = condition
+                            .as_ref()
+                            .map(|condition_name| self.condition_vms(condition_name))
+                            .unwrap_or_default();
+                        MetricTrace {
+                            metric: metric_name,
+                            condition,
+                            vms,
+                        }
+                    })
+                    .collect();
+
+                let mut responsible_entities = entities
+                    .iter()
+                    .filter(|(_, entity)| {
+                        entity
+                            .tlos
+                            .as_ref()
+                            .is_some_and(|entity_tlos| entity_tlos.contains(tlo_name))
+                    })
+                    .map(|(entity_name, _)| entity_name.to_owned())
+                    .collect::<Vec<String>>();
+                responsible_entities.sort();
+
+                tlo_traces.push(TloTrace {
+                    tlo: tlo_name.to_owned(),
+                    evaluation: tlo.evaluation.to_owned(),
+                    metrics: metric_traces,
+                    entities: responsible_entities,
+                });
+            }
+            traceability.goals.push(GoalTrace {
+                goal: goal_name,
+                tlos: tlo_traces,
+            });
+        }
+        traceability
+    }
+
+    fn condition_vms(&self, condition_name: &str) -> Vec<String> {
+        let condition_names = Condition::transitive_conditions(condition_name, &self.conditions);
+
+        let mut vm_names = self
+            .nodes
+            .as_ref()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .filter(|(_, node)| match &node.type_field {
+                        NodeType::VM(vm) => condition_names
+                            .iter()
+                            .any(|condition_name| vm.conditions.contains_key(condition_name)),
+                        _ => false,
+                    })
+                    .map(|(node_name, _)| node_name.to_owned())
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        vm_names.sort();
+        vm_names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_sdl;
+
+    const SDL: &str = r#"
+        name: test-scenario
+        nodes:
+            web:
+                type: VM
+                source: web-server
+                resources:
+                    cpu: 1
+                    ram: 1 gib
+                roles:
+                    admin: root
+                conditions:
+                    web-check: admin
+            mail:
+                type: VM
+                source: mail-server
+                resources:
+                    cpu: 1
+                    ram: 1 gib
+                roles:
+                    admin: root
+                conditions:
+                    mail-check: admin
+        conditions:
+            web-check:
+                command: /usr/bin/web-check.sh
+                interval: 30
+            mail-check:
+                command: /usr/bin/mail-check.sh
+                interval: 30
+            services-up:
+                all-of:
+                    - web-check
+                    - mail-check
+        metrics:
+            metric-1:
+                type: CONDITIONAL
+                max-score: 10
+                condition: services-up
+            metric-2:
+                type: MANUAL
+                artifact: true
+                max-score: 10
+        evaluations:
+            evaluation-1:
+                metrics:
+                    - metric-1
+                    - metric-2
+                min-score: 50
+        tlos:
+            tlo-1:
+                evaluation: evaluation-1
+        goals:
+            goal-1:
+                tlos:
+                    - tlo-1
+        entities:
+            blue-team:
+                role: Blue
+                tlos:
+                    - tlo-1
+                entities:
+                    bob:
+                        role: Blue
+                        tlos:
+                            - tlo-1
+    "#;
+
+    #[test]
+    fn traceability_tree_is_built() {
+        let traceability = parse_sdl(SDL).unwrap().traceability();
+        insta::assert_yaml_snapshot!(traceability);
+    }
+
+    #[test]
+    fn traceability_is_exported_as_csv() {
+        let csv = parse_sdl(SDL).unwrap().traceability().to_csv();
+        insta::assert_snapshot!(csv);
+    }
+
+    #[test]
+    fn traceability_is_exported_as_markdown() {
+        let markdown = parse_sdl(SDL).unwrap().traceability().to_markdown();
+        insta::assert_snapshot!(markdown);
+    }
+
+    #[test]
+    fn traceability_is_exported_as_json() {
+        let json = parse_sdl(SDL).unwrap().traceability().to_json().unwrap();
+        insta::assert_snapshot!(json);
+    }
+}